@@ -1,6 +1,6 @@
 use macros::sf;
 
-use crate::token::{Token, TokenType};
+use crate::token::{Span, Token, TokenType};
 
 pub trait Node {
     fn token_literal(&self) -> &str;
@@ -15,12 +15,20 @@ pub enum Expression {
     },
     IntegerLiteral {
         token: Token,
-        value: i32,
+        value: i64,
+    },
+    FloatLiteral {
+        token: Token,
+        value: f64,
     },
     BooleanLiteral {
         token: Token,
         value: bool,
     },
+    StringLiteral {
+        token: Token,
+        value: String,
+    },
     Prefix {
         token: Token,
         operator: String,
@@ -41,6 +49,7 @@ pub enum Expression {
     FuncExpression {
         token: Token,
         parameters: Vec<Expression>,
+        defaults: Vec<Option<Expression>>,
         body: Box<Statement>,
     },
     CallExpression {
@@ -48,21 +57,54 @@ pub enum Expression {
         func: Box<Expression>,
         args: Vec<Expression>,
     },
+    ArrayLiteral {
+        token: Token,
+        elements: Vec<Expression>,
+    },
+    IndexExpression {
+        token: Token,
+        left: Box<Expression>,
+        index: Box<Expression>,
+    },
+    HashLiteral {
+        token: Token,
+        pairs: Vec<(Expression, Expression)>,
+    },
+    Assign {
+        token: Token,
+        name: Box<Expression>,
+        operator: String,
+        value: Box<Expression>,
+    },
     NoExpression,
 }
 
 impl Expression {
-    fn get_token(&self) -> TokenType {
+    pub fn span(&self) -> Span {
         match self {
-            Expression::Identifier { token, .. } => token.token_type,
-            Expression::IntegerLiteral { token, .. } => token.token_type,
-            Expression::BooleanLiteral { token, .. } => token.token_type,
-            Expression::Prefix { token, .. } => token.token_type,
-            Expression::Infix { token, .. } => token.token_type,
-            Expression::IfExpression { token, .. } => token.token_type,
-            Expression::FuncExpression { token, .. } => token.token_type,
-            Expression::CallExpression { token, .. } => token.token_type,
-            Expression::NoExpression => TokenType::ILLEGAL,
+            Expression::Identifier { token, .. } => token.span,
+            Expression::IntegerLiteral { token, .. } => token.span,
+            Expression::FloatLiteral { token, .. } => token.span,
+            Expression::BooleanLiteral { token, .. } => token.span,
+            Expression::StringLiteral { token, .. } => token.span,
+            Expression::Prefix { token, right, .. } => token.span.to(right.span()),
+            Expression::Infix { left, right, .. } => left.span().to(right.span()),
+            Expression::IfExpression { token, .. } => token.span,
+            Expression::FuncExpression { token, .. } => token.span,
+            Expression::CallExpression {
+                token, func, args, ..
+            } => match args.last() {
+                Some(arg) => func.span().to(arg.span()),
+                None => func.span().to(token.span),
+            },
+            Expression::ArrayLiteral { token, elements } => match elements.last() {
+                Some(elem) => token.span.to(elem.span()),
+                None => token.span,
+            },
+            Expression::IndexExpression { left, index, .. } => left.span().to(index.span()),
+            Expression::HashLiteral { token, .. } => token.span,
+            Expression::Assign { name, value, .. } => name.span().to(value.span()),
+            Expression::NoExpression => Span::default(),
         }
     }
 }
@@ -72,12 +114,18 @@ impl Node for Expression {
         match self {
             Expression::Identifier { token, .. } => &token.literal,
             Expression::IntegerLiteral { token, .. } => &token.literal,
+            Expression::FloatLiteral { token, .. } => &token.literal,
             Expression::BooleanLiteral { token, .. } => &token.literal,
+            Expression::StringLiteral { token, .. } => &token.literal,
             Expression::Prefix { token, .. } => &token.literal,
             Expression::Infix { token, .. } => &token.literal,
             Expression::IfExpression { token, .. } => &token.literal,
             Expression::FuncExpression { token, .. } => &token.literal,
             Expression::CallExpression { token, .. } => &token.literal,
+            Expression::ArrayLiteral { token, .. } => &token.literal,
+            Expression::IndexExpression { token, .. } => &token.literal,
+            Expression::HashLiteral { token, .. } => &token.literal,
+            Expression::Assign { token, .. } => &token.literal,
             Expression::NoExpression => "\0",
         }
     }
@@ -86,7 +134,9 @@ impl Node for Expression {
         match self {
             Expression::Identifier { value, .. } => value.clone(),
             Expression::IntegerLiteral { value, .. } => value.to_string(),
+            Expression::FloatLiteral { value, .. } => format!("{:?}", value),
             Expression::BooleanLiteral { value, .. } => value.to_string(),
+            Expression::StringLiteral { value, .. } => value.clone(),
             Expression::Prefix {
                 operator, right, ..
             } => sf!(format!("({}{})", operator, right.to_string())),
@@ -112,19 +162,24 @@ impl Node for Expression {
                     condition.to_string(),
                     consequence.to_string()
                 ));
-                match *alternative.clone() {
-                    Some(alt) => if_part.push_str(format!(" else {}", alt.to_string()).as_str()),
-                    None => (),
+                if let Some(alt) = alternative.as_ref() {
+                    if_part.push_str(format!(" else {}", alt.to_string()).as_str());
                 }
                 if_part
             }
             Expression::FuncExpression {
-                parameters, body, ..
+                parameters,
+                defaults,
+                body,
+                ..
             } => {
                 let mut func = String::from("fn(");
                 let len = parameters.len();
                 parameters.iter().enumerate().for_each(|(i, param)| {
                     func.push_str(param.to_string().as_str());
+                    if let Some(Some(default)) = defaults.get(i) {
+                        func.push_str(format!(" = {}", default.to_string()).as_str());
+                    }
                     if i != len - 1 {
                         func.push_str(", ");
                     }
@@ -134,7 +189,7 @@ impl Node for Expression {
                 func
             }
             Expression::CallExpression { func, args, .. } => {
-                let mut call = String::from(func.to_string() + "(");
+                let mut call = func.to_string() + "(";
                 let len = args.len();
                 args.iter().enumerate().for_each(|(i, arg)| {
                     call.push_str(arg.to_string().as_str());
@@ -142,9 +197,42 @@ impl Node for Expression {
                         call.push_str(", ");
                     }
                 });
-                call.push_str(")");
+                call.push(')');
                 call
             }
+            Expression::ArrayLiteral { elements, .. } => {
+                let mut array = String::from("[");
+                let len = elements.len();
+                elements.iter().enumerate().for_each(|(i, elem)| {
+                    array.push_str(elem.to_string().as_str());
+                    if i != len - 1 {
+                        array.push_str(", ");
+                    }
+                });
+                array.push(']');
+                array
+            }
+            Expression::IndexExpression { left, index, .. } => {
+                format!("({}[{}])", left.to_string(), index.to_string())
+            }
+            Expression::HashLiteral { pairs, .. } => {
+                let mut hash = String::from("{");
+                let len = pairs.len();
+                pairs.iter().enumerate().for_each(|(i, (key, value))| {
+                    hash.push_str(format!("{}: {}", key.to_string(), value.to_string()).as_str());
+                    if i != len - 1 {
+                        hash.push_str(", ");
+                    }
+                });
+                hash.push('}');
+                hash
+            }
+            Expression::Assign {
+                name,
+                operator,
+                value,
+                ..
+            } => format!("({} {} {})", name.to_string(), operator, value.to_string()),
             Expression::NoExpression => sf!("\0"),
         }
     }
@@ -169,6 +257,23 @@ pub enum Statement {
         token: Token,
         statements: Vec<Statement>,
     },
+    WhileStatement {
+        token: Token,
+        condition: Expression,
+        body: Box<Statement>,
+    },
+    ForStatement {
+        token: Token,
+        variable: Expression,
+        iterable: Expression,
+        body: Box<Statement>,
+    },
+    BreakStatement {
+        token: Token,
+    },
+    ContinueStatement {
+        token: Token,
+    },
 }
 
 impl Node for Statement {
@@ -176,6 +281,10 @@ impl Node for Statement {
         match self {
             Statement::LetStatement { token, .. } => &token.literal,
             Statement::ReturnStatement { token, .. } => &token.literal,
+            Statement::WhileStatement { token, .. } => &token.literal,
+            Statement::ForStatement { token, .. } => &token.literal,
+            Statement::BreakStatement { token } => &token.literal,
+            Statement::ContinueStatement { token } => &token.literal,
             _ => "\0",
         }
     }
@@ -193,9 +302,7 @@ impl Node for Statement {
             Statement::ReturnStatement { token, value } => {
                 format!("{} {:#?};", token.literal, value.to_string())
             }
-            Statement::ExpressionStatement { expression, .. } => {
-                format!("{}", expression.to_string())
-            }
+            Statement::ExpressionStatement { expression, .. } => expression.to_string(),
             Statement::BlockStatement { statements, .. } => {
                 let mut blk_stmt = String::from("{ ");
                 statements
@@ -204,6 +311,23 @@ impl Node for Statement {
                 blk_stmt.push_str(" }");
                 blk_stmt
             }
+            Statement::WhileStatement {
+                condition, body, ..
+            } => format!("while {} {}", condition.to_string(), body.to_string()),
+            Statement::ForStatement {
+                variable,
+                iterable,
+                body,
+                ..
+            } => format!(
+                "for {} in {} {}",
+                variable.to_string(),
+                iterable.to_string(),
+                body.to_string()
+            ),
+            Statement::BreakStatement { token } | Statement::ContinueStatement { token } => {
+                format!("{};", token.literal)
+            }
         }
     }
 }
@@ -212,47 +336,31 @@ impl Statement {
     pub fn new(token_type: TokenType) -> Statement {
         match token_type {
             TokenType::LET => Statement::LetStatement {
-                token: Token {
-                    token_type: TokenType::LET,
-                    literal: sf!("let"),
-                },
+                token: Token::new(TokenType::LET, sf!("let")),
                 name: Expression::Identifier {
-                    token: Token {
-                        token_type: TokenType::LET,
-                        literal: sf!("let"),
-                    },
+                    token: Token::new(TokenType::LET, sf!("let")),
                     value: sf!("let"),
                 },
                 value: Expression::NoExpression,
             },
             TokenType::RETURN => Statement::ReturnStatement {
-                token: Token {
-                    token_type: TokenType::RETURN,
-                    literal: sf!("return"),
-                },
+                token: Token::new(TokenType::RETURN, sf!("return")),
                 value: Expression::NoExpression,
             },
             TokenType::BLOCK => Statement::BlockStatement {
-                token: Token {
-                    token_type: TokenType::BLOCK,
-                    literal: sf!("block"),
-                },
+                token: Token::new(TokenType::BLOCK, sf!("block")),
                 statements: vec![],
             },
             token_type => Statement::ExpressionStatement {
-                token: Token {
-                    token_type: token_type,
-                    literal: sf!("\0"),
-                },
+                token: Token::new(token_type, sf!("\0")),
                 expression: Expression::NoExpression,
             },
         }
     }
 
     pub fn set_let_name(&mut self, ident: Expression) {
-        match self {
-            Statement::LetStatement { ref mut name, .. } => *name = ident,
-            _ => (),
+        if let Statement::LetStatement { ref mut name, .. } = self {
+            *name = ident;
         }
     }
 
@@ -265,41 +373,32 @@ impl Statement {
     }
 
     pub fn set_expression(&mut self, expr: Expression) {
-        match self {
-            Statement::ExpressionStatement {
-                ref mut token,
-                ref mut expression,
-            } => {
-                token.token_type = expr.get_token();
-                *expression = expr
-            }
-            _ => (),
+        if let Statement::ExpressionStatement {
+            ref mut expression, ..
+        } = self
+        {
+            *expression = expr
         }
     }
 
-    pub fn set_expression_literal(&mut self) {
+    // Replaces the placeholder token from `Statement::new` with the one the
+    // statement starts at, so it carries a real span.
+    pub fn set_token(&mut self, stmt_token: Token) {
         match self {
-            Statement::ExpressionStatement {
-                ref mut token,
-                expression,
-            } => token.literal = sf!(expression.token_literal()),
-            _ => (),
-        }
-    }
-
-    pub fn set_block_token(&mut self, blk_token: Token) {
-        match self {
-            Statement::BlockStatement { ref mut token, .. } => *token = blk_token,
+            Statement::LetStatement { ref mut token, .. }
+            | Statement::ReturnStatement { ref mut token, .. }
+            | Statement::ExpressionStatement { ref mut token, .. }
+            | Statement::BlockStatement { ref mut token, .. } => *token = stmt_token,
             _ => (),
         }
     }
 
     pub fn add_block_stmt(&mut self, stmt: Statement) {
-        match self {
-            Statement::BlockStatement {
-                ref mut statements, ..
-            } => statements.push(stmt),
-            _ => (),
+        if let Statement::BlockStatement {
+            ref mut statements, ..
+        } = self
+        {
+            statements.push(stmt);
         }
     }
 }
@@ -0,0 +1,155 @@
+use std::io::{self, Write};
+
+use macros::sf;
+
+use crate::{
+    evaluator::RuntimeError,
+    object::{BuiltinFunction, Object},
+    token::Span,
+};
+
+type NativeFunction = fn(Vec<Object>) -> Result<Object, RuntimeError>;
+
+const BUILTINS: [(&str, NativeFunction); 8] = [
+    ("len", len),
+    ("first", first),
+    ("last", last),
+    ("rest", rest),
+    ("push", push),
+    ("puts", puts),
+    ("print", print),
+    ("type", type_of),
+];
+
+pub fn lookup(name: &str) -> Option<Object> {
+    BUILTINS
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(builtin, func)| Object::Builtin {
+            name: sf!(*builtin),
+            func: BuiltinFunction::new(*func),
+        })
+}
+
+fn len(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 1)?;
+    match &args[0] {
+        Object::String { value } => Ok(Object::Integer {
+            value: value.chars().count() as i64,
+        }),
+        Object::Array { elements } => Ok(Object::Integer {
+            value: elements.len() as i64,
+        }),
+        Object::Hash { pairs } => Ok(Object::Integer {
+            value: pairs.len() as i64,
+        }),
+        arg => Err(RuntimeError::InvalidArgument {
+            message: sf!(format!(
+                "argument to `len` not supported, got {}",
+                arg.get_type()
+            )),
+            span: Span::default(),
+        }),
+    }
+}
+
+fn first(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 1)?;
+    match &args[0] {
+        Object::Array { elements } => Ok(elements.first().cloned().unwrap_or(Object::Null {})),
+        arg => Err(array_arg_error("first", arg)),
+    }
+}
+
+fn last(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 1)?;
+    match &args[0] {
+        Object::Array { elements } => Ok(elements.last().cloned().unwrap_or(Object::Null {})),
+        arg => Err(array_arg_error("last", arg)),
+    }
+}
+
+fn rest(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 1)?;
+    match &args[0] {
+        Object::Array { elements } if elements.is_empty() => Ok(Object::Null {}),
+        Object::Array { elements } => Ok(Object::Array {
+            elements: elements[1..].to_vec(),
+        }),
+        arg => Err(array_arg_error("rest", arg)),
+    }
+}
+
+fn push(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 2)?;
+    match &args[0] {
+        Object::Array { elements } => {
+            let mut elements = elements.clone();
+            elements.push(args[1].clone());
+            Ok(Object::Array { elements })
+        }
+        arg => Err(array_arg_error("push", arg)),
+    }
+}
+
+fn puts(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    puts_to(&mut io::stdout(), args)
+}
+
+fn print(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    print_to(&mut io::stdout(), args)
+}
+
+/// `puts` writing to `out` instead of stdout.
+pub fn puts_to(out: &mut dyn Write, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    args.iter()
+        .try_for_each(|arg| writeln!(out, "{}", arg))
+        .map_err(output_error)?;
+    Ok(Object::Null {})
+}
+
+/// `print` writing to `out`: like `puts` but without newlines, separating
+/// arguments with spaces.
+pub fn print_to(out: &mut dyn Write, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    write!(out, "{}", args.join(" "))
+        .and_then(|_| out.flush())
+        .map_err(output_error)?;
+    Ok(Object::Null {})
+}
+
+fn type_of(args: Vec<Object>) -> Result<Object, RuntimeError> {
+    check_arg_count(&args, 1)?;
+    Ok(Object::String {
+        value: args[0].get_type(),
+    })
+}
+
+fn check_arg_count(args: &[Object], want: usize) -> Result<(), RuntimeError> {
+    if args.len() == want {
+        return Ok(());
+    }
+    Err(RuntimeError::ArityMismatch {
+        want,
+        got: args.len(),
+        span: Span::default(),
+    })
+}
+
+fn output_error(err: io::Error) -> RuntimeError {
+    RuntimeError::Output {
+        message: err.to_string(),
+        span: Span::default(),
+    }
+}
+
+fn array_arg_error(name: &str, arg: &Object) -> RuntimeError {
+    RuntimeError::InvalidArgument {
+        message: sf!(format!(
+            "argument to `{}` must be ARRAY, got {}",
+            name,
+            arg.get_type()
+        )),
+        span: Span::default(),
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    cell::RefCell,
+    fmt, fs,
+    io::{self, Read},
+    rc::Rc,
+};
+
+use macros::sf;
+
+use crate::{
+    environment::Environment,
+    interpreter::DLangError,
+    json,
+    object::Object,
+    repl::{self, Backend},
+};
+
+pub const USAGE: &str = "usage: d_lang [options] [script | -e <code> | -] [args...]
+       d_lang parse [--json] <file>
+
+options:
+  -e <code>         evaluate <code> instead of a script
+  -                 read the script from stdin
+  --vm              run on the bytecode vm instead of the evaluator
+  -q, --quiet       do not print the banner
+      --no-banner   same as --quiet
+  --print-tokens    print the tokens of the script instead of running it
+  --print-ast       print the syntax tree of the script instead of running it
+  -h, --help        print this help
+
+Arguments after the script are available to it as the `args` array.";
+
+// Exit codes for shell use: scripts that fail to parse or run exit with 1 and
+// bad command lines with 2.
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Repl,
+    File(String),
+    Stdin,
+    Eval(String),
+}
+
+impl Source {
+    fn name(&self) -> String {
+        match self {
+            Source::Repl | Source::Stdin => sf!("<stdin>"),
+            Source::File(filename) => filename.clone(),
+            Source::Eval(_) => sf!("<eval>"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub source: Source,
+    pub script_args: Vec<String>,
+    pub backend: Backend,
+    pub banner: bool,
+    pub print_tokens: bool,
+    pub print_ast: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            source: Source::Repl,
+            script_args: vec![],
+            backend: Backend::default(),
+            banner: true,
+            print_tokens: false,
+            print_ast: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(Options),
+    Parse { filename: String, as_json: bool },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError {
+    pub message: String,
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+fn usage_error(message: String) -> UsageError {
+    UsageError { message }
+}
+
+/// Parses the command line, without the program name. Options come first;
+/// everything after the script (or `-e <code>`, `-` or `--`) is passed on to
+/// the script.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    let mut args = args.into_iter().peekable();
+
+    if args.peek().map(String::as_str) == Some("parse") {
+        args.next();
+        return parse_parse_args(args);
+    }
+
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-q" | "--quiet" | "--no-banner" => options.banner = false,
+            "--vm" => options.backend = Backend::Vm,
+            "--print-tokens" => options.print_tokens = true,
+            "--print-ast" => options.print_ast = true,
+            "-e" => match args.next() {
+                Some(code) => {
+                    options.source = Source::Eval(code);
+                    break;
+                }
+                None => return Err(usage_error(sf!("`-e` needs an argument"))),
+            },
+            "-" => {
+                options.source = Source::Stdin;
+                break;
+            }
+            "--" => {
+                if let Some(filename) = args.next() {
+                    options.source = Source::File(filename);
+                }
+                break;
+            }
+            flag if flag.starts_with('-') => {
+                return Err(usage_error(format!("unknown option `{}`", flag)))
+            }
+            _ => {
+                options.source = Source::File(arg);
+                break;
+            }
+        }
+    }
+    options.script_args = args.collect();
+
+    if options.source == Source::Repl && (options.print_tokens || options.print_ast) {
+        return Err(usage_error(sf!(
+            "`--print-tokens` and `--print-ast` need a script"
+        )));
+    }
+
+    Ok(Command::Run(options))
+}
+
+fn parse_parse_args<I: Iterator<Item = String>>(args: I) -> Result<Command, UsageError> {
+    let mut as_json = false;
+    let mut filename = None;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => as_json = true,
+            flag if flag.starts_with('-') => {
+                return Err(usage_error(format!("unknown option `{}`", flag)))
+            }
+            _ if filename.is_some() => {
+                return Err(usage_error(format!("unexpected argument `{}`", arg)))
+            }
+            _ => filename = Some(arg),
+        }
+    }
+
+    match filename {
+        Some(filename) => Ok(Command::Parse { filename, as_json }),
+        None => Err(usage_error(sf!("`parse` needs a file"))),
+    }
+}
+
+/// Runs a parsed command line and returns the process exit code.
+pub fn run(command: Command) -> i32 {
+    match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            0
+        }
+        Command::Parse { filename, as_json } => parse_file(&filename, as_json),
+        Command::Run(Options {
+            source: Source::Repl,
+            backend,
+            ..
+        }) => {
+            repl::run(backend);
+            0
+        }
+        Command::Run(options) => run_script(&options),
+    }
+}
+
+fn read_source(source: &Source) -> Result<String, String> {
+    match source {
+        Source::File(filename) => fs::read_to_string(filename)
+            .map_err(|err| format!("cannot read `{}`: {}", filename, err)),
+        Source::Stdin => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|err| format!("cannot read stdin: {}", err))?;
+            Ok(input)
+        }
+        Source::Eval(code) => Ok(code.clone()),
+        Source::Repl => Ok(String::new()),
+    }
+}
+
+fn run_script(options: &Options) -> i32 {
+    let input = match read_source(&options.source) {
+        Ok(input) => input,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return EXIT_FAILURE;
+        }
+    };
+    let filename = options.source.name();
+
+    if options.print_tokens {
+        repl::print_tokens(&input, &filename);
+    }
+    if options.print_ast {
+        return exit_code(repl::print_ast(&input, &filename));
+    }
+    if options.print_tokens {
+        return 0;
+    }
+
+    let env = Rc::new(RefCell::new(Environment::new()));
+    let args = options
+        .script_args
+        .iter()
+        .map(|arg| Object::String { value: arg.clone() })
+        .collect();
+    env.borrow_mut()
+        .set(sf!("args"), Object::Array { elements: args });
+
+    exit_code(repl::lpe(input, &filename, &env, options.backend))
+}
+
+/// Prints the syntax tree of a file, as JSON when `as_json` is set and as
+/// source text otherwise.
+fn parse_file(filename: &str, as_json: bool) -> i32 {
+    let input = match read_source(&Source::File(filename.to_string())) {
+        Ok(input) => input,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return EXIT_FAILURE;
+        }
+    };
+
+    if !as_json {
+        return exit_code(repl::print_ast(&input, filename));
+    }
+    match repl::parse(&input, filename) {
+        Ok(program) => {
+            println!("{}", json::to_json(&program));
+            0
+        }
+        Err(_) => EXIT_FAILURE,
+    }
+}
+
+fn exit_code<T>(result: Result<T, DLangError>) -> i32 {
+    match result {
+        Ok(_) => 0,
+        Err(_) => EXIT_FAILURE,
+    }
+}
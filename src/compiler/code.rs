@@ -0,0 +1,233 @@
+use std::fmt::Write;
+
+use crate::token::Span;
+
+use super::CompileError;
+
+pub type Instructions = Vec<u8>;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Constant,
+    Pop,
+    True,
+    False,
+    Null,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Equal,
+    NotEqual,
+    LessThan,
+    GreaterThan,
+    Minus,
+    Bang,
+    Jump,
+    JumpNotTruthy,
+    GetName,
+    Define,
+    Array,
+    Hash,
+    Index,
+    Call,
+    ReturnValue,
+    Closure,
+    Default,
+    Range,
+    Iter,
+    IterNext,
+    IterEnd,
+    PushScope,
+    PopScope,
+    SetName,
+    LessEqual,
+    GreaterEqual,
+    Mod,
+    PushLoop,
+    PopLoop,
+    Unwind,
+}
+
+// Indexed by the opcode byte, so the order must match the enum.
+const OPCODES: [Opcode; 39] = [
+    Opcode::Constant,
+    Opcode::Pop,
+    Opcode::True,
+    Opcode::False,
+    Opcode::Null,
+    Opcode::Add,
+    Opcode::Sub,
+    Opcode::Mul,
+    Opcode::Div,
+    Opcode::Equal,
+    Opcode::NotEqual,
+    Opcode::LessThan,
+    Opcode::GreaterThan,
+    Opcode::Minus,
+    Opcode::Bang,
+    Opcode::Jump,
+    Opcode::JumpNotTruthy,
+    Opcode::GetName,
+    Opcode::Define,
+    Opcode::Array,
+    Opcode::Hash,
+    Opcode::Index,
+    Opcode::Call,
+    Opcode::ReturnValue,
+    Opcode::Closure,
+    Opcode::Default,
+    Opcode::Range,
+    Opcode::Iter,
+    Opcode::IterNext,
+    Opcode::IterEnd,
+    Opcode::PushScope,
+    Opcode::PopScope,
+    Opcode::SetName,
+    Opcode::LessEqual,
+    Opcode::GreaterEqual,
+    Opcode::Mod,
+    Opcode::PushLoop,
+    Opcode::PopLoop,
+    Opcode::Unwind,
+];
+
+impl Opcode {
+    pub fn from_byte(byte: u8) -> Option<Opcode> {
+        OPCODES.get(byte as usize).copied()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Opcode::Constant => "OpConstant",
+            Opcode::Pop => "OpPop",
+            Opcode::True => "OpTrue",
+            Opcode::False => "OpFalse",
+            Opcode::Null => "OpNull",
+            Opcode::Add => "OpAdd",
+            Opcode::Sub => "OpSub",
+            Opcode::Mul => "OpMul",
+            Opcode::Div => "OpDiv",
+            Opcode::Equal => "OpEqual",
+            Opcode::NotEqual => "OpNotEqual",
+            Opcode::LessThan => "OpLessThan",
+            Opcode::GreaterThan => "OpGreaterThan",
+            Opcode::Minus => "OpMinus",
+            Opcode::Bang => "OpBang",
+            Opcode::Jump => "OpJump",
+            Opcode::JumpNotTruthy => "OpJumpNotTruthy",
+            Opcode::GetName => "OpGetName",
+            Opcode::Define => "OpDefine",
+            Opcode::Array => "OpArray",
+            Opcode::Hash => "OpHash",
+            Opcode::Index => "OpIndex",
+            Opcode::Call => "OpCall",
+            Opcode::ReturnValue => "OpReturnValue",
+            Opcode::Closure => "OpClosure",
+            Opcode::Default => "OpDefault",
+            Opcode::Range => "OpRange",
+            Opcode::Iter => "OpIter",
+            Opcode::IterNext => "OpIterNext",
+            Opcode::IterEnd => "OpIterEnd",
+            Opcode::PushScope => "OpPushScope",
+            Opcode::PopScope => "OpPopScope",
+            Opcode::SetName => "OpSetName",
+            Opcode::LessEqual => "OpLessEqual",
+            Opcode::GreaterEqual => "OpGreaterEqual",
+            Opcode::Mod => "OpMod",
+            Opcode::PushLoop => "OpPushLoop",
+            Opcode::PopLoop => "OpPopLoop",
+            Opcode::Unwind => "OpUnwind",
+        }
+    }
+
+    /// Byte width of each operand, all encoded big-endian.
+    pub fn operand_widths(self) -> &'static [usize] {
+        match self {
+            Opcode::Constant
+            | Opcode::Jump
+            | Opcode::JumpNotTruthy
+            | Opcode::GetName
+            | Opcode::SetName
+            | Opcode::Define
+            | Opcode::Array
+            | Opcode::Hash
+            | Opcode::Closure
+            | Opcode::IterNext => &[2],
+            Opcode::Call => &[1],
+            Opcode::Default => &[1, 2],
+            _ => &[],
+        }
+    }
+}
+
+/// Encodes one instruction, failing if an operand does not fit its width.
+pub fn make(op: Opcode, operands: &[usize]) -> Result<Instructions, CompileError> {
+    let mut instruction = vec![op as u8];
+    for (operand, width) in operands.iter().zip(op.operand_widths()) {
+        let out_of_range = || CompileError::OperandOutOfRange {
+            opcode: op.name().to_string(),
+            operand: *operand,
+            max: (1 << (8 * width)) - 1,
+            span: Span::default(),
+        };
+        match width {
+            1 => instruction.push(u8::try_from(*operand).map_err(|_| out_of_range())?),
+            2 => {
+                let operand = u16::try_from(*operand).map_err(|_| out_of_range())?;
+                instruction.extend_from_slice(&operand.to_be_bytes());
+            }
+            _ => unreachable!("unsupported operand width {}", width),
+        }
+    }
+    Ok(instruction)
+}
+
+pub fn read_operand(width: usize, ins: &[u8]) -> usize {
+    match width {
+        1 => ins[0] as usize,
+        2 => u16::from_be_bytes([ins[0], ins[1]]) as usize,
+        _ => unreachable!("unsupported operand width {}", width),
+    }
+}
+
+/// Decodes the operands following an opcode and returns them with the
+/// number of bytes read.
+pub fn read_operands(op: Opcode, ins: &[u8]) -> (Vec<usize>, usize) {
+    let mut offset = 0;
+    let operands = op
+        .operand_widths()
+        .iter()
+        .map(|width| {
+            let operand = read_operand(*width, &ins[offset..]);
+            offset += width;
+            operand
+        })
+        .collect();
+    (operands, offset)
+}
+
+/// Renders one instruction per line as `offset name operands...`.
+pub fn disassemble(ins: &[u8]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < ins.len() {
+        let op = match Opcode::from_byte(ins[i]) {
+            Some(op) => op,
+            None => {
+                let _ = writeln!(out, "{:04} ERROR: unknown opcode {}", i, ins[i]);
+                i += 1;
+                continue;
+            }
+        };
+        let (operands, read) = read_operands(op, &ins[i + 1..]);
+        let _ = write!(out, "{:04} {}", i, op.name());
+        for operand in operands {
+            let _ = write!(out, " {}", operand);
+        }
+        out.push('\n');
+        i += 1 + read;
+    }
+    out
+}
@@ -0,0 +1,75 @@
+use std::{error::Error, fmt};
+
+use crate::token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    OperandOutOfRange {
+        opcode: String,
+        operand: usize,
+        max: usize,
+        span: Span,
+    },
+    MissingExpression {
+        span: Span,
+    },
+    UnknownOperator {
+        operator: String,
+        span: Span,
+    },
+    OutsideLoop {
+        keyword: String,
+        span: Span,
+    },
+}
+
+impl CompileError {
+    pub fn span(&self) -> Span {
+        match self {
+            CompileError::OperandOutOfRange { span, .. } => *span,
+            CompileError::MissingExpression { span } => *span,
+            CompileError::UnknownOperator { span, .. } => *span,
+            CompileError::OutsideLoop { span, .. } => *span,
+        }
+    }
+
+    // Like `RuntimeError::with_span`, only fills in a missing position.
+    pub fn with_span(mut self, span: Span) -> CompileError {
+        let err_span = match &mut self {
+            CompileError::OperandOutOfRange { span, .. } => span,
+            CompileError::MissingExpression { span } => span,
+            CompileError::UnknownOperator { span, .. } => span,
+            CompileError::OutsideLoop { span, .. } => span,
+        };
+        if *err_span == Span::default() {
+            *err_span = span;
+        }
+        self
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::OperandOutOfRange {
+                opcode,
+                operand,
+                max,
+                ..
+            } => write!(
+                f,
+                "program too large: {} operand {} exceeds {}",
+                opcode, operand, max
+            ),
+            CompileError::MissingExpression { .. } => write!(f, "missing expression"),
+            CompileError::UnknownOperator { operator, .. } => {
+                write!(f, "unknown operator: {}", operator)
+            }
+            CompileError::OutsideLoop { keyword, .. } => {
+                write!(f, "`{}` outside of a loop", keyword)
+            }
+        }
+    }
+}
+
+impl Error for CompileError {}
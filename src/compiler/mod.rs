@@ -0,0 +1,417 @@
+pub mod code;
+mod compile_error;
+
+pub use compile_error::CompileError;
+
+use std::rc::Rc;
+
+use crate::{
+    ast::{Expression, Node, Statement},
+    object::Object,
+    token::{Span, Token},
+};
+
+use code::{make, Instructions, Opcode};
+
+/// A unit of bytecode with its own constant and name pools, so closures stay
+/// valid after the program that created them has finished.
+#[derive(Debug, Default)]
+pub struct CompiledFunction {
+    pub instructions: Instructions,
+    pub constants: Vec<Object>,
+    pub names: Vec<String>,
+    pub functions: Vec<Rc<CompiledFunction>>,
+    pub parameters: Vec<String>,
+    pub required: usize,
+    pub source: String,
+    spans: Vec<(usize, Span)>,
+}
+
+impl CompiledFunction {
+    /// Span of the expression that emitted the instruction at `offset`.
+    pub fn span_at(&self, offset: usize) -> Span {
+        match self.spans.binary_search_by_key(&offset, |(pos, _)| *pos) {
+            Ok(i) => self.spans[i].1,
+            Err(_) => Span::default(),
+        }
+    }
+}
+
+/// Lowers a program to the function the vm runs at the top level. Its value
+/// is the value of the last statement, like `eval_statements`.
+pub fn compile(statements: &[Statement]) -> Result<CompiledFunction, CompileError> {
+    let mut c = Compiler::default();
+    c.compile_block(statements)?;
+    c.emit(Opcode::ReturnValue, &[], Span::default())?;
+    Ok(c.function)
+}
+
+#[derive(Default)]
+struct Compiler {
+    function: CompiledFunction,
+    loops: Vec<Loop>,
+}
+
+// Jumps out of the loop being compiled, patched once the loop is complete.
+#[derive(Default)]
+struct Loop {
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+impl Compiler {
+    fn emit(&mut self, op: Opcode, operands: &[usize], span: Span) -> Result<usize, CompileError> {
+        let instruction = make(op, operands).map_err(|err| err.with_span(span))?;
+        let pos = self.function.instructions.len();
+        self.function.spans.push((pos, span));
+        self.function.instructions.extend(instruction);
+        Ok(pos)
+    }
+
+    fn change_operands(&mut self, pos: usize, operands: &[usize]) -> Result<(), CompileError> {
+        let op = Opcode::from_byte(self.function.instructions[pos]).expect("invalid opcode");
+        let instruction =
+            make(op, operands).map_err(|err| err.with_span(self.function.span_at(pos)))?;
+        self.function.instructions[pos..pos + instruction.len()].copy_from_slice(&instruction);
+        Ok(())
+    }
+
+    fn add_constant(&mut self, obj: Object) -> usize {
+        self.function.constants.push(obj);
+        self.function.constants.len() - 1
+    }
+
+    fn add_name(&mut self, name: &str) -> usize {
+        match self.function.names.iter().position(|n| n == name) {
+            Some(i) => i,
+            None => {
+                self.function.names.push(name.to_string());
+                self.function.names.len() - 1
+            }
+        }
+    }
+
+    // Every statement leaves exactly one value on the stack; a block keeps
+    // only the value of its last statement.
+    fn compile_block(&mut self, statements: &[Statement]) -> Result<(), CompileError> {
+        if statements.is_empty() {
+            self.emit(Opcode::Null, &[], Span::default())?;
+        }
+        for (i, stmt) in statements.iter().enumerate() {
+            self.compile_statement(stmt)?;
+            if i != statements.len() - 1 {
+                self.emit(Opcode::Pop, &[], Span::default())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn compile_statement(&mut self, stmt: &Statement) -> Result<(), CompileError> {
+        match stmt {
+            Statement::LetStatement { name, value, .. } => {
+                self.compile_expr(value)?;
+                let idx = self.add_name(&name.to_string());
+                self.emit(Opcode::Define, &[idx], name.span())?;
+            }
+            Statement::ReturnStatement { value, .. } => {
+                self.compile_expr(value)?;
+                self.emit(Opcode::ReturnValue, &[], Span::default())?;
+            }
+            Statement::ExpressionStatement { expression, .. } => self.compile_expr(expression)?,
+            Statement::BlockStatement { statements, .. } => self.compile_block(statements)?,
+            Statement::WhileStatement {
+                token,
+                condition,
+                body,
+            } => self.compile_while(condition, body, token.span)?,
+            Statement::ForStatement {
+                token,
+                variable,
+                iterable,
+                body,
+            } => self.compile_for(variable, iterable, body, token.span)?,
+            // Both jump away, so the value they would leave is never needed.
+            // They may sit in a block inside a half-evaluated expression, so
+            // its operands are dropped first.
+            Statement::BreakStatement { token } => {
+                self.emit(Opcode::Unwind, &[], token.span)?;
+                let jump = self.emit(Opcode::Jump, &[0], token.span)?;
+                self.current_loop(token)?.breaks.push(jump);
+            }
+            Statement::ContinueStatement { token } => {
+                self.emit(Opcode::Unwind, &[], token.span)?;
+                let jump = self.emit(Opcode::Jump, &[0], token.span)?;
+                self.current_loop(token)?.continues.push(jump);
+            }
+        }
+        Ok(())
+    }
+
+    // The body's value is dropped after every iteration and the loop itself
+    // leaves null. The condition is part of the loop, so `break` and
+    // `continue` in it act on this loop.
+    fn compile_while(
+        &mut self,
+        condition: &Expression,
+        body: &Statement,
+        span: Span,
+    ) -> Result<(), CompileError> {
+        self.emit(Opcode::PushLoop, &[], span)?;
+        self.loops.push(Loop::default());
+
+        let start = self.function.instructions.len();
+        self.compile_expr(condition)?;
+        let jump_not_truthy = self.emit(Opcode::JumpNotTruthy, &[0], span)?;
+
+        self.compile_statement(body)?;
+        self.emit(Opcode::Pop, &[], Span::default())?;
+        self.emit(Opcode::Jump, &[start], span)?;
+
+        let end_pos = self.emit(Opcode::PopLoop, &[], span)?;
+        self.change_operands(jump_not_truthy, &[end_pos])?;
+        self.patch_loop(start, end_pos)?;
+        self.emit(Opcode::Null, &[], span)?;
+        Ok(())
+    }
+
+    // The iterator lives in the frame until `OpIterEnd`, and every iteration
+    // runs in a scope of its own that `break` and `continue` also leave.
+    fn compile_for(
+        &mut self,
+        variable: &Expression,
+        iterable: &Expression,
+        body: &Statement,
+        span: Span,
+    ) -> Result<(), CompileError> {
+        self.compile_expr(iterable)?;
+        self.emit(Opcode::Iter, &[], iterable.span())?;
+        self.emit(Opcode::PushLoop, &[], span)?;
+
+        let start = self.function.instructions.len();
+        let iter_next = self.emit(Opcode::IterNext, &[0], span)?;
+        self.emit(Opcode::PushScope, &[], span)?;
+        let idx = self.add_name(&variable.to_string());
+        self.emit(Opcode::Define, &[idx], variable.span())?;
+        self.emit(Opcode::Pop, &[], Span::default())?;
+
+        self.loops.push(Loop::default());
+        self.compile_statement(body)?;
+        self.emit(Opcode::Pop, &[], Span::default())?;
+        let next_pos = self.emit(Opcode::PopScope, &[], span)?;
+        self.emit(Opcode::Jump, &[start], span)?;
+        let break_pos = self.emit(Opcode::PopScope, &[], span)?;
+        self.patch_loop(next_pos, break_pos)?;
+
+        let end_pos = self.emit(Opcode::IterEnd, &[], span)?;
+        self.change_operands(iter_next, &[end_pos])?;
+        self.emit(Opcode::PopLoop, &[], span)?;
+        self.emit(Opcode::Null, &[], span)?;
+        Ok(())
+    }
+
+    // The parser rejects break and continue outside loops, but a hand-built
+    // AST may not.
+    fn current_loop(&mut self, token: &Token) -> Result<&mut Loop, CompileError> {
+        self.loops
+            .last_mut()
+            .ok_or_else(|| CompileError::OutsideLoop {
+                keyword: token.literal.clone(),
+                span: token.span,
+            })
+    }
+
+    fn patch_loop(&mut self, continue_pos: usize, break_pos: usize) -> Result<(), CompileError> {
+        let exit = self.loops.pop().expect("no loop to patch");
+        for jump in exit.continues {
+            self.change_operands(jump, &[continue_pos])?;
+        }
+        for jump in exit.breaks {
+            self.change_operands(jump, &[break_pos])?;
+        }
+        Ok(())
+    }
+
+    fn compile_expr(&mut self, expr: &Expression) -> Result<(), CompileError> {
+        let span = expr.span();
+        match expr {
+            Expression::Identifier { value, .. } => {
+                let idx = self.add_name(value);
+                self.emit(Opcode::GetName, &[idx], span)?;
+            }
+            Expression::IntegerLiteral { value, .. } => {
+                let idx = self.add_constant(Object::Integer { value: *value });
+                self.emit(Opcode::Constant, &[idx], span)?;
+            }
+            Expression::FloatLiteral { value, .. } => {
+                let idx = self.add_constant(Object::Float { value: *value });
+                self.emit(Opcode::Constant, &[idx], span)?;
+            }
+            Expression::BooleanLiteral { value, .. } => {
+                let op = if *value { Opcode::True } else { Opcode::False };
+                self.emit(op, &[], span)?;
+            }
+            Expression::StringLiteral { value, .. } => {
+                let idx = self.add_constant(Object::String {
+                    value: value.clone(),
+                });
+                self.emit(Opcode::Constant, &[idx], span)?;
+            }
+            Expression::Prefix {
+                operator, right, ..
+            } => {
+                self.compile_expr(right)?;
+                let op = match operator.as_str() {
+                    "!" => Opcode::Bang,
+                    "-" => Opcode::Minus,
+                    _ => return Err(unknown_operator(operator, span)),
+                };
+                self.emit(op, &[], span)?;
+            }
+            Expression::Infix {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                self.compile_expr(left)?;
+                self.compile_expr(right)?;
+                self.emit(infix_opcode(operator, span)?, &[], span)?;
+            }
+            Expression::IfExpression {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                self.compile_expr(condition)?;
+                let jump_not_truthy = self.emit(Opcode::JumpNotTruthy, &[0], span)?;
+                self.compile_statement(consequence)?;
+                let jump = self.emit(Opcode::Jump, &[0], span)?;
+
+                let alternative_pos = self.function.instructions.len();
+                self.change_operands(jump_not_truthy, &[alternative_pos])?;
+                match alternative.as_ref() {
+                    Some(alt) => self.compile_statement(alt)?,
+                    None => {
+                        self.emit(Opcode::Null, &[], span)?;
+                    }
+                }
+
+                let end_pos = self.function.instructions.len();
+                self.change_operands(jump, &[end_pos])?;
+            }
+            Expression::FuncExpression {
+                parameters,
+                defaults,
+                body,
+                ..
+            } => {
+                let function = compile_function(parameters, defaults, body, expr.to_string())?;
+                self.function.functions.push(Rc::new(function));
+                let idx = self.function.functions.len() - 1;
+                self.emit(Opcode::Closure, &[idx], span)?;
+            }
+            Expression::CallExpression { func, args, .. } => {
+                self.compile_expr(func)?;
+                for arg in args {
+                    self.compile_expr(arg)?;
+                }
+                self.emit(Opcode::Call, &[args.len()], span)?;
+            }
+            Expression::ArrayLiteral { elements, .. } => {
+                for elem in elements {
+                    self.compile_expr(elem)?;
+                }
+                self.emit(Opcode::Array, &[elements.len()], span)?;
+            }
+            Expression::IndexExpression { left, index, .. } => {
+                self.compile_expr(left)?;
+                self.compile_expr(index)?;
+                self.emit(Opcode::Index, &[], span)?;
+            }
+            Expression::HashLiteral { pairs, .. } => {
+                for (key, value) in pairs {
+                    self.compile_expr(key)?;
+                    self.compile_expr(value)?;
+                }
+                self.emit(Opcode::Hash, &[pairs.len()], span)?;
+            }
+            Expression::Assign {
+                name,
+                operator,
+                value,
+                ..
+            } => {
+                match operator.strip_suffix('=').filter(|op| !op.is_empty()) {
+                    Some(infix) => {
+                        self.compile_expr(name)?;
+                        self.compile_expr(value)?;
+                        self.emit(infix_opcode(infix, span)?, &[], span)?;
+                    }
+                    None => self.compile_expr(value)?,
+                }
+                let idx = self.add_name(&name.to_string());
+                self.emit(Opcode::SetName, &[idx], span)?;
+            }
+            Expression::NoExpression => return Err(CompileError::MissingExpression { span }),
+        }
+        Ok(())
+    }
+}
+
+// Each defaulted parameter gets a prologue that is skipped when the caller
+// passed an argument for it, so defaults are evaluated at call time.
+fn compile_function(
+    parameters: &[Expression],
+    defaults: &[Option<Expression>],
+    body: &Statement,
+    source: String,
+) -> Result<CompiledFunction, CompileError> {
+    let mut c = Compiler::default();
+
+    for (i, (param, default)) in parameters.iter().zip(defaults).enumerate() {
+        if let Some(default) = default {
+            let skip = c.emit(Opcode::Default, &[i, 0], param.span())?;
+            c.compile_expr(default)?;
+            let idx = c.add_name(&param.to_string());
+            c.emit(Opcode::Define, &[idx], param.span())?;
+            c.emit(Opcode::Pop, &[], Span::default())?;
+            let end_pos = c.function.instructions.len();
+            c.change_operands(skip, &[i, end_pos])?;
+        }
+    }
+
+    c.compile_statement(body)?;
+    c.emit(Opcode::ReturnValue, &[], Span::default())?;
+
+    c.function.parameters = parameters.iter().map(|p| p.to_string()).collect();
+    c.function.required = defaults.iter().filter(|d| d.is_none()).count();
+    c.function.source = source;
+    Ok(c.function)
+}
+
+fn infix_opcode(operator: &str, span: Span) -> Result<Opcode, CompileError> {
+    let op = match operator {
+        "+" => Opcode::Add,
+        "-" => Opcode::Sub,
+        "*" => Opcode::Mul,
+        "/" => Opcode::Div,
+        "%" => Opcode::Mod,
+        "==" => Opcode::Equal,
+        "!=" => Opcode::NotEqual,
+        "<" => Opcode::LessThan,
+        ">" => Opcode::GreaterThan,
+        "<=" => Opcode::LessEqual,
+        ">=" => Opcode::GreaterEqual,
+        ".." => Opcode::Range,
+        _ => return Err(unknown_operator(operator, span)),
+    };
+    Ok(op)
+}
+
+fn unknown_operator(operator: &str, span: Span) -> CompileError {
+    CompileError::UnknownOperator {
+        operator: operator.to_string(),
+        span,
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{compiler::CompileError, evaluator::RuntimeError, parser::ParseError, token::Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, span: Span) -> Diagnostic {
+        Diagnostic { message, span }
+    }
+
+    // Renders the diagnostic like rustc does:
+    //
+    // error: type mismatch: INTEGER + BOOLEAN
+    //  --> sample.dl:1:1
+    //   |
+    // 1 | 5 + true;
+    //   | ^^^^^^^^
+    pub fn render(&self, source: &str, filename: &str) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        if self.span.line == 0 {
+            return out;
+        }
+
+        let line_no = self.span.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        let line: Vec<char> = source
+            .lines()
+            .nth(self.span.line - 1)
+            .unwrap_or("")
+            .chars()
+            .collect();
+
+        // Keep tabs so the carets line up with the source line.
+        let start = (self.span.column - 1).min(line.len());
+        let pad: String = line[..start]
+            .iter()
+            .map(|ch| if *ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let len = self.span.len.min(line.len() - start).max(1);
+
+        out.push_str(&format!(
+            "{}--> {}:{}:{}\n",
+            gutter, filename, self.span.line, self.span.column
+        ));
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!(
+            "{} | {}\n",
+            line_no,
+            line.iter().collect::<String>()
+        ));
+        out.push_str(&format!("{} | {}{}\n", gutter, pad, "^".repeat(len)));
+        out
+    }
+}
+
+impl From<&ParseError> for Diagnostic {
+    fn from(err: &ParseError) -> Diagnostic {
+        Diagnostic::new(err.to_string(), err.span())
+    }
+}
+
+impl From<&CompileError> for Diagnostic {
+    fn from(err: &CompileError) -> Diagnostic {
+        Diagnostic::new(err.to_string(), err.span())
+    }
+}
+
+impl From<&RuntimeError> for Diagnostic {
+    fn from(err: &RuntimeError) -> Diagnostic {
+        Diagnostic::new(err.to_string(), err.span())
+    }
+}
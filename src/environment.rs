@@ -1,23 +0,0 @@
-use std::collections::HashMap;
-
-use crate::object::Object;
-
-pub struct Environment {
-    store: HashMap<String, Object>,
-}
-
-impl Environment {
-    pub fn new() -> Environment {
-        Environment {
-            store: HashMap::new(),
-        }
-    }
-
-    pub fn get(&self, name: String) -> Option<&Object> {
-        self.store.get(&name)
-    }
-
-    pub fn set(&mut self, name: String, val: Object) {
-        self.store.insert(name, val);
-    }
-}
@@ -0,0 +1,119 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use crate::object::Object;
+
+use super::Environment;
+
+// Scopes are not looked at until this many have been created.
+const MIN_THRESHOLD: usize = 1024;
+
+thread_local! {
+    static SCOPES: RefCell<Vec<Weak<RefCell<Environment>>>> = const { RefCell::new(vec![]) };
+    static THRESHOLD: Cell<usize> = const { Cell::new(MIN_THRESHOLD) };
+}
+
+// Registers a scope, collecting once twice as many have been created as
+// were left alive after the last collection.
+pub(super) fn track(scope: &Rc<RefCell<Environment>>) {
+    let due = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.push(Rc::downgrade(scope));
+        scopes.len() >= THRESHOLD.with(|threshold| threshold.get())
+    });
+    if due {
+        collect();
+    }
+}
+
+/// Frees the scopes that are kept alive only by functions stored in scopes
+/// that are themselves unreachable, such as a call's scope holding a function
+/// defined in it. Runs on its own as scopes are created.
+pub fn collect() {
+    let scopes: Vec<Rc<RefCell<Environment>>> = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.retain(|scope| scope.strong_count() > 0);
+        scopes.iter().filter_map(Weak::upgrade).collect()
+    });
+    let index: HashMap<*const RefCell<Environment>, usize> = scopes
+        .iter()
+        .enumerate()
+        .map(|(i, scope)| (Rc::as_ptr(scope), i))
+        .collect();
+
+    // References each scope gets from the other scopes. A scope that is
+    // borrowed right now is left alone, so whatever it refers to counts as
+    // referenced from outside.
+    let mut internal = vec![0; scopes.len()];
+    let mut children: Vec<Vec<usize>> = vec![vec![]; scopes.len()];
+    let mut busy = vec![false; scopes.len()];
+    for (i, scope) in scopes.iter().enumerate() {
+        let Ok(scope) = scope.try_borrow() else {
+            busy[i] = true;
+            continue;
+        };
+        let mut refs = vec![];
+        scope
+            .outer
+            .iter()
+            .for_each(|outer| refs.push(Rc::as_ptr(outer)));
+        scope
+            .store
+            .values()
+            .for_each(|value| scope_refs(value, &mut refs));
+        for child in refs.iter().filter_map(|r| index.get(r)) {
+            internal[*child] += 1;
+            children[i].push(*child);
+        }
+    }
+
+    // Anything with a reference from outside the scopes, the handle taken
+    // above aside, is live, and so is everything it reaches.
+    let mut live: Vec<usize> = (0..scopes.len())
+        .filter(|i| busy[*i] || Rc::strong_count(&scopes[*i]) - 1 > internal[*i])
+        .collect();
+    let mut reached = vec![false; scopes.len()];
+    live.iter().for_each(|i| reached[*i] = true);
+    while let Some(i) = live.pop() {
+        for child in &children[i] {
+            if !reached[*child] {
+                reached[*child] = true;
+                live.push(*child);
+            }
+        }
+    }
+
+    // Emptying the unreachable scopes breaks their cycles; the bindings are
+    // dropped once no scope is borrowed.
+    let garbage: Vec<HashMap<String, Object>> = scopes
+        .iter()
+        .zip(&reached)
+        .filter(|(_, reached)| !**reached)
+        .map(|(scope, _)| std::mem::take(&mut scope.borrow_mut().store))
+        .collect();
+    drop(scopes);
+    drop(garbage);
+    let alive = SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        scopes.retain(|scope| scope.strong_count() > 0);
+        scopes.len()
+    });
+    THRESHOLD.with(|threshold| threshold.set(MIN_THRESHOLD.max(2 * alive)));
+}
+
+// The scopes a value holds on to, directly or through its elements.
+fn scope_refs(value: &Object, refs: &mut Vec<*const RefCell<Environment>>) {
+    match value {
+        Object::Function { env, .. } | Object::Closure { env, .. } => refs.push(Rc::as_ptr(env)),
+        Object::Array { elements } => elements.iter().for_each(|e| scope_refs(e, refs)),
+        Object::Hash { pairs } => pairs.values().for_each(|(key, value)| {
+            scope_refs(key, refs);
+            scope_refs(value, refs);
+        }),
+        Object::Return { value } => scope_refs(value, refs),
+        _ => {}
+    }
+}
@@ -0,0 +1,87 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use crate::object::Object;
+
+mod collect;
+
+pub use collect::collect;
+
+#[derive(Clone, Default)]
+pub struct Environment {
+    store: HashMap<String, Object>,
+    outer: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment {
+            store: HashMap::new(),
+            outer: None,
+        }
+    }
+
+    pub fn new_enclosed(outer: Rc<RefCell<Environment>>) -> Environment {
+        Environment {
+            store: HashMap::new(),
+            outer: Some(outer),
+        }
+    }
+
+    /// A scope for a call or a loop iteration. Unlike one made with
+    /// `new_enclosed`, it is freed by `collect` once it is only kept alive
+    /// by functions defined in it.
+    pub fn new_scope(outer: Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        let scope = Rc::new(RefCell::new(Environment::new_enclosed(outer)));
+        collect::track(&scope);
+        scope
+    }
+
+    pub fn get(&self, name: String) -> Option<Object> {
+        match self.store.get(&name) {
+            Some(obj) => Some(obj.clone()),
+            None => match &self.outer {
+                Some(outer) => outer.borrow().get(name),
+                None => None,
+            },
+        }
+    }
+
+    pub fn set(&mut self, name: String, val: Object) {
+        self.store.insert(name, val);
+    }
+
+    /// Rebinds `name` in the nearest scope that has it. Returns false when no
+    /// scope does.
+    pub fn assign(&mut self, name: String, val: Object) -> bool {
+        if let Some(slot) = self.store.get_mut(&name) {
+            *slot = val;
+            return true;
+        }
+        match &self.outer {
+            Some(outer) => outer.borrow_mut().assign(name, val),
+            None => false,
+        }
+    }
+
+    /// The bindings of this scope, without the outer ones, sorted by name.
+    pub fn bindings(&self) -> Vec<(String, Object)> {
+        let mut bindings: Vec<(String, Object)> = self
+            .store
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+}
+
+// Functions capture the environment they are defined in, which usually holds
+// the function itself, so only the bound names are printed to avoid recursing.
+impl fmt::Debug for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Environment")
+            .field("store", &self.store.keys().collect::<Vec<&String>>())
+            .field("outer", &self.outer.is_some())
+            .finish()
+    }
+}
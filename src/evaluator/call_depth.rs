@@ -0,0 +1,68 @@
+use std::cell::Cell;
+
+use crate::token::Span;
+
+use super::RuntimeError;
+
+// The native stack grows on demand, so this only stops runaway recursion;
+// ordinary recursive code should not come near it.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 100_000;
+
+thread_local! {
+    static MAX_CALL_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_CALL_DEPTH) };
+    static CALL_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Sets how many calls may be nested before a `StackOverflow` error is
+/// raised, for every evaluator and vm run on the current thread. Tail calls
+/// do not count towards the limit.
+pub fn set_max_call_depth(depth: usize) {
+    MAX_CALL_DEPTH.with(|max| max.set(depth));
+}
+
+pub fn max_call_depth() -> usize {
+    MAX_CALL_DEPTH.with(|max| max.get())
+}
+
+/// Applies a limit for as long as it is alive, then restores the previous
+/// one, so a session's limit does not leak into other runs on the thread.
+pub(crate) struct DepthLimit(usize);
+
+impl DepthLimit {
+    pub(crate) fn apply(depth: usize) -> DepthLimit {
+        let previous = max_call_depth();
+        set_max_call_depth(depth);
+        DepthLimit(previous)
+    }
+}
+
+impl Drop for DepthLimit {
+    fn drop(&mut self) {
+        set_max_call_depth(self.0);
+    }
+}
+
+/// Marks one nested evaluator call for as long as it is alive.
+pub(crate) struct CallGuard;
+
+impl CallGuard {
+    pub(crate) fn enter() -> Result<CallGuard, RuntimeError> {
+        let limit = max_call_depth();
+        CALL_DEPTH.with(|depth| {
+            if depth.get() >= limit {
+                return Err(RuntimeError::StackOverflow {
+                    limit,
+                    span: Span::default(),
+                });
+            }
+            depth.set(depth.get() + 1);
+            Ok(CallGuard)
+        })
+    }
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        CALL_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
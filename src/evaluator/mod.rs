@@ -0,0 +1,777 @@
+mod call_depth;
+mod runtime_error;
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use macros::sf;
+
+use crate::{
+    ast::{Expression, Node, Statement},
+    builtins,
+    environment::Environment,
+    object::{HashKey, Object},
+    token::Span,
+};
+
+pub use call_depth::{max_call_depth, set_max_call_depth, DEFAULT_MAX_CALL_DEPTH};
+
+pub(crate) use call_depth::DepthLimit;
+pub use runtime_error::RuntimeError;
+
+use call_depth::CallGuard;
+
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT_SIZE: usize = 4 * 1024 * 1024;
+
+pub fn eval_statements(
+    statements: Vec<Statement>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, RuntimeError> {
+    match eval_block(statements, env)? {
+        Object::Return { value } => Ok(*value),
+        result => Ok(result),
+    }
+}
+
+// Runs statements up to the first `return`, `break` or `continue`, which is
+// handed on to the enclosing function or loop.
+fn eval_block(
+    statements: Vec<Statement>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, RuntimeError> {
+    let mut result = Object::Null {};
+    for stmt in statements {
+        result = eval(stmt, env)?;
+        if is_control_flow(&result) {
+            return Ok(result);
+        }
+    }
+    Ok(result)
+}
+
+fn is_control_flow(obj: &Object) -> bool {
+    matches!(
+        obj,
+        Object::Return { .. } | Object::Break {} | Object::Continue {}
+    )
+}
+
+// Why an expression stopped early: an error, or a `return`, `break` or
+// `continue` in a block inside it, as in `[1, if (c) { break; }]`. The rest of
+// the expression is skipped and the statement holding it passes the control
+// flow on.
+enum Unwind {
+    Error(RuntimeError),
+    Flow(Object),
+}
+
+impl Unwind {
+    fn with_span(self, span: Span) -> Unwind {
+        match self {
+            Unwind::Error(err) => Unwind::Error(err.with_span(span)),
+            flow => flow,
+        }
+    }
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(err: RuntimeError) -> Self {
+        Unwind::Error(err)
+    }
+}
+
+// Hands an expression's result to the statement holding it, turning control
+// flow back into the object that `eval_block` and the loops look for.
+fn settle(result: Result<Object, Unwind>) -> Result<Object, RuntimeError> {
+    match result {
+        Ok(obj) | Err(Unwind::Flow(obj)) => Ok(obj),
+        Err(Unwind::Error(err)) => Err(err),
+    }
+}
+
+pub fn eval(stmt: Statement, env: &Rc<RefCell<Environment>>) -> Result<Object, RuntimeError> {
+    match stmt {
+        Statement::LetStatement { name, value, .. } => {
+            let evaluated = settle(eval_expr(value, env))?;
+            if is_control_flow(&evaluated) {
+                return Ok(evaluated);
+            }
+            if let Expression::Identifier {
+                value: key_name, ..
+            } = name
+            {
+                env.borrow_mut().set(key_name, evaluated.clone());
+            }
+            Ok(evaluated)
+        }
+        Statement::ReturnStatement { value, .. } => match eval_expr(value, env) {
+            Ok(value) => Ok(Object::Return {
+                value: Box::new(value),
+            }),
+            result => settle(result),
+        },
+        Statement::ExpressionStatement { expression, .. } => settle(eval_expr(expression, env)),
+        Statement::BlockStatement { statements, .. } => eval_block(statements, env),
+        Statement::WhileStatement {
+            condition, body, ..
+        } => eval_while(condition, *body, env),
+        Statement::ForStatement {
+            variable,
+            iterable,
+            body,
+            ..
+        } => eval_for(variable.to_string(), iterable, *body, env),
+        Statement::BreakStatement { .. } => Ok(Object::Break {}),
+        Statement::ContinueStatement { .. } => Ok(Object::Continue {}),
+    }
+}
+
+// A loop is worth null; a `return` in its body leaves the enclosing function.
+// The condition belongs to the loop too, so a `break` or `continue` in it acts
+// on this loop.
+fn eval_while(
+    condition: Expression,
+    body: Statement,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, RuntimeError> {
+    loop {
+        match settle(eval_expr(condition.clone(), env))? {
+            Object::Break {} => break,
+            Object::Continue {} => continue,
+            result @ Object::Return { .. } => return Ok(result),
+            condition if !is_truthy(&condition) => break,
+            _ => {}
+        }
+        match eval(body.clone(), env)? {
+            Object::Break {} => break,
+            result @ Object::Return { .. } => return Ok(result),
+            _ => {}
+        }
+    }
+    Ok(Object::Null {})
+}
+
+// Every iteration runs in a scope of its own holding the loop variable, so
+// neither it nor `let`s in the body outlive the iteration.
+fn eval_for(
+    variable: String,
+    iterable: Expression,
+    body: Statement,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, RuntimeError> {
+    let span = iterable.span();
+    let iterable = settle(eval_expr(iterable, env))?;
+    if is_control_flow(&iterable) {
+        return Ok(iterable);
+    }
+    let items = iterate(iterable).map_err(|err| err.with_span(span))?;
+    for item in items {
+        let scope = Environment::new_scope(Rc::clone(env));
+        scope.borrow_mut().set(variable.clone(), item);
+        match eval(body.clone(), &scope)? {
+            Object::Break {} => break,
+            result @ Object::Return { .. } => return Ok(result),
+            _ => {}
+        }
+    }
+    Ok(Object::Null {})
+}
+
+// What a `for` loop visits: array elements, the integers of a range, which
+// are produced one at a time, and hash keys in key order.
+pub(crate) fn iterate(obj: Object) -> Result<Box<dyn Iterator<Item = Object>>, RuntimeError> {
+    match obj {
+        Object::Array { elements } => Ok(Box::new(elements.into_iter())),
+        Object::Range { start, end } => Ok(Box::new(
+            (start..end).map(|value| Object::Integer { value }),
+        )),
+        Object::Hash { pairs } => Ok(Box::new(pairs.into_values().map(|(key, _)| key))),
+        _ => Err(RuntimeError::NotIterable {
+            type_name: obj.get_type(),
+            span: Span::default(),
+        }),
+    }
+}
+
+fn eval_expr(expr: Expression, env: &Rc<RefCell<Environment>>) -> Result<Object, Unwind> {
+    let span = expr.span();
+    eval_expr_kind(expr, env).map_err(|err| err.with_span(span))
+}
+
+fn eval_expr_kind(expr: Expression, env: &Rc<RefCell<Environment>>) -> Result<Object, Unwind> {
+    match expr {
+        Expression::Identifier { value, .. } => {
+            let ident = env.borrow().get(value.clone());
+            if let Some(obj) = ident {
+                return Ok(obj);
+            }
+            if let Some(builtin) = builtins::lookup(&value) {
+                return Ok(builtin);
+            }
+            Err(Unwind::Error(RuntimeError::UnknownIdentifier {
+                name: value,
+                span: Span::default(),
+            }))
+        }
+        Expression::IntegerLiteral { value, .. } => Ok(Object::Integer { value }),
+        Expression::FloatLiteral { value, .. } => Ok(Object::Float { value }),
+        Expression::BooleanLiteral { value, .. } => Ok(Object::Boolean { value }),
+        Expression::StringLiteral { value, .. } => Ok(Object::String { value }),
+        Expression::Prefix {
+            operator, right, ..
+        } => {
+            let evaluated = eval_expr(*right, env)?;
+            Ok(eval_prefix_expr(operator, evaluated)?)
+        }
+        Expression::Infix {
+            left,
+            operator,
+            right,
+            ..
+        } => {
+            let evaluated_left = eval_expr(*left, env)?;
+            let evaluated_right = eval_expr(*right, env)?;
+            Ok(eval_infix_expr(evaluated_left, operator, evaluated_right)?)
+        }
+        Expression::IfExpression {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => eval_if_expr(*condition, *consequence, *alternative, env),
+        Expression::FuncExpression {
+            parameters,
+            defaults,
+            body,
+            ..
+        } => Ok(Object::Function {
+            parameters,
+            defaults,
+            body,
+            env: Rc::clone(env),
+        }),
+        Expression::CallExpression { func, args, .. } => {
+            let function = eval_expr(*func, env)?;
+            let args = eval_args(args, env)?;
+            Ok(apply_function(function, args)?)
+        }
+        Expression::ArrayLiteral { elements, .. } => Ok(Object::Array {
+            elements: eval_args(elements, env)?,
+        }),
+        Expression::IndexExpression { left, index, .. } => {
+            let left = eval_expr(*left, env)?;
+            let index = eval_expr(*index, env)?;
+            Ok(eval_index_expr(left, index)?)
+        }
+        Expression::HashLiteral { pairs, .. } => eval_hash_literal(pairs, env),
+        Expression::Assign {
+            name,
+            operator,
+            value,
+            ..
+        } => eval_assign(*name, operator, *value, env),
+        Expression::NoExpression => todo!(),
+    }
+}
+
+// `x op= v` reads `x` before evaluating `v`, as `x = x op v` would.
+fn eval_assign(
+    name: Expression,
+    operator: String,
+    value: Expression,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, Unwind> {
+    let value = match operator.strip_suffix('=').filter(|op| !op.is_empty()) {
+        Some(infix) => {
+            let current = eval_expr(name.clone(), env)?;
+            eval_infix_expr(current, sf!(infix), eval_expr(value, env)?)?
+        }
+        None => eval_expr(value, env)?,
+    };
+    Ok(assign(env, name.to_string(), value)?)
+}
+
+pub(crate) fn assign(
+    env: &Rc<RefCell<Environment>>,
+    name: String,
+    value: Object,
+) -> Result<Object, RuntimeError> {
+    if env.borrow_mut().assign(name.clone(), value.clone()) {
+        return Ok(value);
+    }
+    Err(RuntimeError::UndeclaredAssignment {
+        name,
+        span: Span::default(),
+    })
+}
+
+pub(crate) fn eval_prefix_expr(operator: String, right: Object) -> Result<Object, RuntimeError> {
+    match operator.as_str() {
+        "!" => Ok(eval_bang_operator_expr(right)),
+        "-" => eval_minus_operator_expr(right),
+        _ => Err(RuntimeError::UnknownOperator {
+            left: None,
+            operator,
+            right: right.get_type(),
+            span: Span::default(),
+        }),
+    }
+}
+
+fn eval_bang_operator_expr(right: Object) -> Object {
+    match right {
+        Object::Boolean { value } => Object::Boolean { value: !value },
+        _ => Object::Boolean { value: false },
+    }
+}
+
+fn eval_minus_operator_expr(right: Object) -> Result<Object, RuntimeError> {
+    match right {
+        Object::Integer { value } => match value.checked_neg() {
+            Some(value) => Ok(Object::Integer { value }),
+            None => Err(integer_overflow(None, "-", value)),
+        },
+        Object::Float { value } => Ok(Object::Float { value: -value }),
+        _ => Err(RuntimeError::UnknownOperator {
+            left: None,
+            operator: String::from("-"),
+            right: right.get_type(),
+            span: Span::default(),
+        }),
+    }
+}
+
+pub(crate) fn eval_infix_expr(
+    left: Object,
+    operator: String,
+    right: Object,
+) -> Result<Object, RuntimeError> {
+    let evaluated = match (&left, &right) {
+        (Object::Integer { value: left_val }, Object::Integer { value: right_val }) => {
+            eval_int_infix_expr(*left_val, &operator, *right_val)?
+        }
+        (Object::Float { value: left_val }, Object::Float { value: right_val }) => {
+            eval_float_infix_expr(*left_val, &operator, *right_val)?
+        }
+        // Mixed arithmetic promotes the integer to a float.
+        (Object::Integer { value: left_val }, Object::Float { value: right_val }) => {
+            eval_float_infix_expr(*left_val as f64, &operator, *right_val)?
+        }
+        (Object::Float { value: left_val }, Object::Integer { value: right_val }) => {
+            eval_float_infix_expr(*left_val, &operator, *right_val as f64)?
+        }
+        (Object::Boolean { value: left_val }, Object::Boolean { value: right_val }) => {
+            eval_bool_infix_expr(*left_val, &operator, *right_val)
+        }
+        (Object::String { value: left_val }, Object::String { value: right_val }) => {
+            eval_string_infix_expr(left_val, &operator, right_val)
+        }
+        _ if left.get_type() != right.get_type() => {
+            return Err(RuntimeError::TypeMismatch {
+                left: left.get_type(),
+                operator,
+                right: right.get_type(),
+                span: Span::default(),
+            })
+        }
+        _ => None,
+    };
+
+    evaluated.ok_or(RuntimeError::UnknownOperator {
+        left: Some(left.get_type()),
+        operator,
+        right: right.get_type(),
+        span: Span::default(),
+    })
+}
+
+fn eval_int_infix_expr(
+    left: i64,
+    operator: &str,
+    right: i64,
+) -> Result<Option<Object>, RuntimeError> {
+    // Overflow is an error rather than a wrap or a panic.
+    let checked = |value: Option<i64>| match value {
+        Some(value) => Ok(Object::Integer { value }),
+        None => Err(integer_overflow(Some(left), operator, right)),
+    };
+
+    Ok(Some(match operator {
+        "+" => checked(left.checked_add(right))?,
+        "-" => checked(left.checked_sub(right))?,
+        "*" => checked(left.checked_mul(right))?,
+        "/" => {
+            if right == 0 {
+                return Err(RuntimeError::DivisionByZero {
+                    span: Span::default(),
+                });
+            }
+            checked(left.checked_div(right))?
+        }
+        "%" => {
+            if right == 0 {
+                return Err(RuntimeError::DivisionByZero {
+                    span: Span::default(),
+                });
+            }
+            checked(left.checked_rem(right))?
+        }
+        "<" => Object::Boolean {
+            value: left < right,
+        },
+        ">" => Object::Boolean {
+            value: left > right,
+        },
+        "<=" => Object::Boolean {
+            value: left <= right,
+        },
+        ">=" => Object::Boolean {
+            value: left >= right,
+        },
+        ".." => Object::Range {
+            start: left,
+            end: right,
+        },
+        "==" => Object::Boolean {
+            value: left == right,
+        },
+        "!=" => Object::Boolean {
+            value: left != right,
+        },
+        _ => return Ok(None),
+    }))
+}
+
+fn integer_overflow(left: Option<i64>, operator: &str, right: i64) -> RuntimeError {
+    RuntimeError::IntegerOverflow {
+        left,
+        operator: operator.to_string(),
+        right,
+        span: Span::default(),
+    }
+}
+
+fn eval_float_infix_expr(
+    left: f64,
+    operator: &str,
+    right: f64,
+) -> Result<Option<Object>, RuntimeError> {
+    Ok(Some(match operator {
+        "+" => Object::Float {
+            value: left + right,
+        },
+        "-" => Object::Float {
+            value: left - right,
+        },
+        "*" => Object::Float {
+            value: left * right,
+        },
+        "/" => {
+            if right == 0.0 {
+                return Err(RuntimeError::DivisionByZero {
+                    span: Span::default(),
+                });
+            }
+            Object::Float {
+                value: left / right,
+            }
+        }
+        "%" => {
+            if right == 0.0 {
+                return Err(RuntimeError::DivisionByZero {
+                    span: Span::default(),
+                });
+            }
+            Object::Float {
+                value: left % right,
+            }
+        }
+        "<" => Object::Boolean {
+            value: left < right,
+        },
+        ">" => Object::Boolean {
+            value: left > right,
+        },
+        "<=" => Object::Boolean {
+            value: left <= right,
+        },
+        ">=" => Object::Boolean {
+            value: left >= right,
+        },
+        "==" => Object::Boolean {
+            value: left == right,
+        },
+        "!=" => Object::Boolean {
+            value: left != right,
+        },
+        _ => return Ok(None),
+    }))
+}
+
+fn eval_bool_infix_expr(left: bool, operator: &str, right: bool) -> Option<Object> {
+    match operator {
+        "==" => Some(Object::Boolean {
+            value: left == right,
+        }),
+        "!=" => Some(Object::Boolean {
+            value: left != right,
+        }),
+        _ => None,
+    }
+}
+
+fn eval_string_infix_expr(left: &str, operator: &str, right: &str) -> Option<Object> {
+    match operator {
+        "+" => Some(Object::String {
+            value: format!("{}{}", left, right),
+        }),
+        "==" => Some(Object::Boolean {
+            value: left == right,
+        }),
+        "!=" => Some(Object::Boolean {
+            value: left != right,
+        }),
+        _ => None,
+    }
+}
+
+fn eval_if_expr(
+    condition: Expression,
+    consequence: Statement,
+    alternative: Option<Statement>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, Unwind> {
+    let result = if is_truthy(&eval_expr(condition, env)?) {
+        eval(consequence, env)?
+    } else if let Some(stmt) = alternative {
+        eval(stmt, env)?
+    } else {
+        Object::Null {}
+    };
+    if is_control_flow(&result) {
+        return Err(Unwind::Flow(result));
+    }
+    Ok(result)
+}
+
+pub(crate) fn is_truthy(obj: &Object) -> bool {
+    match obj {
+        Object::Boolean { value } => *value,
+        Object::Null {} => false,
+        _ => true,
+    }
+}
+
+pub(crate) fn eval_index_expr(left: Object, index: Object) -> Result<Object, RuntimeError> {
+    match (&left, &index) {
+        (Object::Array { elements }, Object::Integer { value }) => {
+            Ok(eval_array_index_expr(elements, *value))
+        }
+        (Object::Hash { pairs }, _) => eval_hash_index_expr(pairs, &index),
+        _ => Err(RuntimeError::IndexNotSupported {
+            left: left.get_type(),
+            index: index.get_type(),
+            span: Span::default(),
+        }),
+    }
+}
+
+// Negative indices count back from the end; anything out of range is null.
+fn eval_array_index_expr(elements: &[Object], index: i64) -> Object {
+    let len = elements.len() as i64;
+    let idx = if index < 0 { len + index } else { index };
+    if idx < 0 || idx >= len {
+        return Object::Null {};
+    }
+    elements[idx as usize].clone()
+}
+
+fn eval_hash_index_expr(
+    pairs: &BTreeMap<HashKey, (Object, Object)>,
+    index: &Object,
+) -> Result<Object, RuntimeError> {
+    let key = hash_key(index)?;
+    match pairs.get(&key) {
+        Some((_, value)) => Ok(value.clone()),
+        None => Ok(Object::Null {}),
+    }
+}
+
+fn eval_hash_literal(
+    pairs: Vec<(Expression, Expression)>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, Unwind> {
+    let mut evaluated_pairs = BTreeMap::new();
+    for (key_expr, value_expr) in pairs {
+        let key = eval_expr(key_expr, env)?;
+        let hash_key = hash_key(&key)?;
+        let value = eval_expr(value_expr, env)?;
+        evaluated_pairs.insert(hash_key, (key, value));
+    }
+    Ok(Object::Hash {
+        pairs: evaluated_pairs,
+    })
+}
+
+pub(crate) fn hash_key(key: &Object) -> Result<HashKey, RuntimeError> {
+    key.hash_key().ok_or(RuntimeError::UnusableHashKey {
+        type_name: key.get_type(),
+        span: Span::default(),
+    })
+}
+
+fn eval_args(args: Vec<Expression>, env: &Rc<RefCell<Environment>>) -> Result<Vec<Object>, Unwind> {
+    args.into_iter().map(|arg| eval_expr(arg, env)).collect()
+}
+
+// The value of a statement in tail position: either a plain value or a call
+// whose result becomes the result of the enclosing function.
+enum Tail {
+    Value(Object),
+    Call(Object, Vec<Object>, Span),
+}
+
+// Non-tail calls recurse through `eval`, so they count towards the maximum
+// call depth and grow the host stack on demand instead of overflowing it.
+pub(crate) fn apply_function(function: Object, args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let _guard = CallGuard::enter()?;
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, || {
+        apply_tail_calls(function, args)
+    })
+}
+
+// Calls in tail position come back to this loop instead of recursing, so tail
+// recursion runs in constant stack.
+fn apply_tail_calls(mut function: Object, mut args: Vec<Object>) -> Result<Object, RuntimeError> {
+    let mut call_span = Span::default();
+    loop {
+        match function {
+            Object::Function {
+                parameters,
+                defaults,
+                body,
+                env,
+            } => {
+                let result = extend_function_env(&parameters, &defaults, args, env)
+                    .map_err(|err| err.with_span(call_span))
+                    .and_then(|env| eval_tail(*body, &env, true));
+                match result {
+                    Ok(Tail::Value(Object::Return { value }))
+                    | Err(Unwind::Flow(Object::Return { value })) => return Ok(*value),
+                    Ok(Tail::Value(evaluated)) | Err(Unwind::Flow(evaluated)) => {
+                        return Ok(evaluated)
+                    }
+                    Ok(Tail::Call(next, next_args, span)) => {
+                        function = next;
+                        args = next_args;
+                        call_span = span;
+                    }
+                    Err(Unwind::Error(err)) => return Err(err),
+                }
+            }
+            Object::Builtin { func, .. } => {
+                return func.call(args).map_err(|err| err.with_span(call_span))
+            }
+            _ => {
+                return Err(RuntimeError::NotCallable {
+                    type_name: function.get_type(),
+                    span: call_span,
+                })
+            }
+        }
+    }
+}
+
+// Mirrors `eval` for statements that may end the function: `return`s and,
+// when `last` is set because its value becomes the function result, the
+// statement itself. Calls found there are left to `apply_tail_calls`.
+fn eval_tail(stmt: Statement, env: &Rc<RefCell<Environment>>, last: bool) -> Result<Tail, Unwind> {
+    match stmt {
+        Statement::ReturnStatement { value, .. } => Ok(match eval_tail_expr(value, env, true)? {
+            Tail::Value(value) if is_control_flow(&value) => Tail::Value(value),
+            Tail::Value(value) => Tail::Value(Object::Return {
+                value: Box::new(value),
+            }),
+            call => call,
+        }),
+        Statement::ExpressionStatement { expression, .. } => eval_tail_expr(expression, env, last),
+        Statement::BlockStatement { statements, .. } => {
+            let mut result = Object::Null {};
+            let last_index = statements.len().saturating_sub(1);
+            for (i, stmt) in statements.into_iter().enumerate() {
+                match eval_tail(stmt, env, last && i == last_index)? {
+                    Tail::Value(value) if is_control_flow(&value) => return Ok(Tail::Value(value)),
+                    Tail::Value(value) => result = value,
+                    call => return Ok(call),
+                }
+            }
+            Ok(Tail::Value(result))
+        }
+        stmt => Ok(Tail::Value(eval(stmt, env)?)),
+    }
+}
+
+fn eval_tail_expr(
+    expr: Expression,
+    env: &Rc<RefCell<Environment>>,
+    last: bool,
+) -> Result<Tail, Unwind> {
+    let span = expr.span();
+    match expr {
+        Expression::CallExpression { func, args, .. } if last => {
+            let function = eval_expr(*func, env)?;
+            let args = eval_args(args, env)?;
+            Ok(Tail::Call(function, args, span))
+        }
+        Expression::IfExpression {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            if is_truthy(&eval_expr(*condition, env)?) {
+                eval_tail(*consequence, env, last)
+            } else if let Some(stmt) = *alternative {
+                eval_tail(stmt, env, last)
+            } else {
+                Ok(Tail::Value(Object::Null {}))
+            }
+        }
+        expr => Ok(Tail::Value(eval_expr(expr, env)?)),
+    }
+}
+
+pub(crate) fn check_arity(required: usize, max: usize, got: usize) -> Result<(), RuntimeError> {
+    if got < required || got > max {
+        return Err(RuntimeError::ArityMismatch {
+            want: if got < required { required } else { max },
+            got,
+            span: Span::default(),
+        });
+    }
+    Ok(())
+}
+
+// Missing arguments take their parameter's default, evaluated inside the call
+// so that defaults may refer to the parameters before them.
+fn extend_function_env(
+    parameters: &[Expression],
+    defaults: &[Option<Expression>],
+    args: Vec<Object>,
+    outer: Rc<RefCell<Environment>>,
+) -> Result<Rc<RefCell<Environment>>, Unwind> {
+    let required = defaults.iter().filter(|d| d.is_none()).count();
+    check_arity(required, parameters.len(), args.len())?;
+
+    let env = Environment::new_scope(outer);
+    let mut args = args.into_iter();
+    for (param, default) in parameters.iter().zip(defaults) {
+        let value = match (args.next(), default) {
+            (Some(arg), _) => arg,
+            (None, Some(default)) => eval_expr(default.clone(), &env)?,
+            (None, None) => Object::Null {},
+        };
+        env.borrow_mut().set(param.to_string(), value);
+    }
+    Ok(env)
+}
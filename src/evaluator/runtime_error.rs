@@ -0,0 +1,182 @@
+use std::{error::Error, fmt};
+
+use crate::token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+    UnknownIdentifier {
+        name: String,
+        span: Span,
+    },
+    TypeMismatch {
+        left: String,
+        operator: String,
+        right: String,
+        span: Span,
+    },
+    UnknownOperator {
+        left: Option<String>,
+        operator: String,
+        right: String,
+        span: Span,
+    },
+    NotCallable {
+        type_name: String,
+        span: Span,
+    },
+    ArityMismatch {
+        want: usize,
+        got: usize,
+        span: Span,
+    },
+    DivisionByZero {
+        span: Span,
+    },
+    IntegerOverflow {
+        left: Option<i64>,
+        operator: String,
+        right: i64,
+        span: Span,
+    },
+    IndexNotSupported {
+        left: String,
+        index: String,
+        span: Span,
+    },
+    UnusableHashKey {
+        type_name: String,
+        span: Span,
+    },
+    NotIterable {
+        type_name: String,
+        span: Span,
+    },
+    UndeclaredAssignment {
+        name: String,
+        span: Span,
+    },
+    InvalidArgument {
+        message: String,
+        span: Span,
+    },
+    StackOverflow {
+        limit: usize,
+        span: Span,
+    },
+    Output {
+        message: String,
+        span: Span,
+    },
+}
+
+impl RuntimeError {
+    pub fn span(&self) -> Span {
+        match self {
+            RuntimeError::UnknownIdentifier { span, .. } => *span,
+            RuntimeError::TypeMismatch { span, .. } => *span,
+            RuntimeError::UnknownOperator { span, .. } => *span,
+            RuntimeError::NotCallable { span, .. } => *span,
+            RuntimeError::ArityMismatch { span, .. } => *span,
+            RuntimeError::DivisionByZero { span } => *span,
+            RuntimeError::IntegerOverflow { span, .. } => *span,
+            RuntimeError::IndexNotSupported { span, .. } => *span,
+            RuntimeError::UnusableHashKey { span, .. } => *span,
+            RuntimeError::NotIterable { span, .. } => *span,
+            RuntimeError::UndeclaredAssignment { span, .. } => *span,
+            RuntimeError::InvalidArgument { span, .. } => *span,
+            RuntimeError::StackOverflow { span, .. } => *span,
+            RuntimeError::Output { span, .. } => *span,
+        }
+    }
+
+    // Errors are raised without a position; the innermost expression that
+    // sees one gives it its span and outer expressions leave it alone.
+    pub fn with_span(mut self, span: Span) -> RuntimeError {
+        let err_span = match &mut self {
+            RuntimeError::UnknownIdentifier { span, .. } => span,
+            RuntimeError::TypeMismatch { span, .. } => span,
+            RuntimeError::UnknownOperator { span, .. } => span,
+            RuntimeError::NotCallable { span, .. } => span,
+            RuntimeError::ArityMismatch { span, .. } => span,
+            RuntimeError::DivisionByZero { span } => span,
+            RuntimeError::IntegerOverflow { span, .. } => span,
+            RuntimeError::IndexNotSupported { span, .. } => span,
+            RuntimeError::UnusableHashKey { span, .. } => span,
+            RuntimeError::NotIterable { span, .. } => span,
+            RuntimeError::UndeclaredAssignment { span, .. } => span,
+            RuntimeError::InvalidArgument { span, .. } => span,
+            RuntimeError::StackOverflow { span, .. } => span,
+            RuntimeError::Output { span, .. } => span,
+        };
+        if *err_span == Span::default() {
+            *err_span = span;
+        }
+        self
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::UnknownIdentifier { name, .. } => {
+                write!(f, "identifier not found: {}", name)
+            }
+            RuntimeError::TypeMismatch {
+                left,
+                operator,
+                right,
+                ..
+            } => write!(f, "type mismatch: {} {} {}", left, operator, right),
+            RuntimeError::UnknownOperator {
+                left: Some(left),
+                operator,
+                right,
+                ..
+            } => write!(f, "unknown operator: {} {} {}", left, operator, right),
+            RuntimeError::UnknownOperator {
+                left: None,
+                operator,
+                right,
+                ..
+            } => write!(f, "unknown operator: {}{}", operator, right),
+            RuntimeError::NotCallable { type_name, .. } => {
+                write!(f, "not a function: {}", type_name)
+            }
+            RuntimeError::ArityMismatch { want, got, .. } => {
+                write!(f, "wrong number of arguments: want={}, got={}", want, got)
+            }
+            RuntimeError::DivisionByZero { .. } => write!(f, "division by zero"),
+            RuntimeError::IntegerOverflow {
+                left: Some(left),
+                operator,
+                right,
+                ..
+            } => write!(f, "integer overflow: {} {} {}", left, operator, right),
+            RuntimeError::IntegerOverflow {
+                left: None,
+                operator,
+                right,
+                ..
+            } => write!(f, "integer overflow: {}({})", operator, right),
+            RuntimeError::IndexNotSupported { left, index, .. } => {
+                write!(f, "index operator not supported: {}[{}]", left, index)
+            }
+            RuntimeError::UnusableHashKey { type_name, .. } => {
+                write!(f, "unusable as hash key: {}", type_name)
+            }
+            RuntimeError::NotIterable { type_name, .. } => {
+                write!(f, "cannot iterate over {}", type_name)
+            }
+            RuntimeError::UndeclaredAssignment { name, .. } => {
+                write!(f, "assignment to undeclared variable: {}", name)
+            }
+            RuntimeError::InvalidArgument { message, .. } => write!(f, "{}", message),
+            RuntimeError::StackOverflow { limit, .. } => {
+                write!(f, "stack overflow: call depth exceeded {}", limit)
+            }
+            RuntimeError::Output { message, .. } => write!(f, "cannot write output: {}", message),
+        }
+    }
+}
+
+impl Error for RuntimeError {}
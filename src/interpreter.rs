@@ -0,0 +1,110 @@
+use std::{cell::RefCell, error::Error, fmt, io::Write, rc::Rc};
+
+use macros::sf;
+
+use crate::{
+    builtins,
+    compiler::CompileError,
+    environment::Environment,
+    evaluator::{self, eval_statements, DepthLimit, RuntimeError},
+    lexer::Lexer,
+    object::{BuiltinFunction, Object},
+    parser::{ParseError, Parser},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DLangError {
+    Parse(Vec<ParseError>),
+    Compile(CompileError),
+    Runtime(RuntimeError),
+}
+
+impl fmt::Display for DLangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DLangError::Parse(errors) => {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "parse error: {}", errors.join("; "))
+            }
+            DLangError::Compile(err) => write!(f, "compile error: {}", err),
+            DLangError::Runtime(err) => write!(f, "runtime error: {}", err),
+        }
+    }
+}
+
+impl Error for DLangError {}
+
+/// A d_lang session that keeps its global bindings between evaluations, for
+/// hosting scripts inside a Rust application.
+pub struct Interpreter {
+    env: Rc<RefCell<Environment>>,
+    max_call_depth: usize,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        Interpreter {
+            env: Rc::new(RefCell::new(Environment::new())),
+            max_call_depth: evaluator::DEFAULT_MAX_CALL_DEPTH,
+        }
+    }
+
+    /// Parses and evaluates `src` against the session globals and returns the
+    /// value of the last statement.
+    pub fn eval_str(&mut self, src: &str) -> Result<Object, DLangError> {
+        let l = Lexer::new(src.chars().collect());
+        let mut p = Parser::new(l);
+
+        let program = p.parse_program().map_err(DLangError::Parse)?;
+
+        let _limit = DepthLimit::apply(self.max_call_depth);
+        eval_statements(program.statements, &self.env).map_err(DLangError::Runtime)
+    }
+
+    pub fn set_global(&mut self, name: &str, value: Object) {
+        self.env.borrow_mut().set(sf!(name), value);
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Object> {
+        self.env.borrow().get(sf!(name))
+    }
+
+    /// Limits how deeply non-tail calls may nest before scripts fail with a
+    /// stack overflow error. Only scripts run by this interpreter are affected.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Makes `func` callable from scripts as `name`, shadowing any builtin of
+    /// the same name. Closures may capture state from the host application.
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        func: impl Fn(Vec<Object>) -> Result<Object, RuntimeError> + 'static,
+    ) {
+        self.set_global(
+            name,
+            Object::Builtin {
+                name: sf!(name),
+                func: BuiltinFunction::new(func),
+            },
+        );
+    }
+
+    /// Sends the output of `puts` and `print` to `output` instead of stdout.
+    pub fn set_output(&mut self, output: Rc<RefCell<dyn Write>>) {
+        let puts_output = Rc::clone(&output);
+        self.register_function("puts", move |args| {
+            builtins::puts_to(&mut *puts_output.borrow_mut(), args)
+        });
+        self.register_function("print", move |args| {
+            builtins::print_to(&mut *output.borrow_mut(), args)
+        });
+    }
+}
@@ -0,0 +1,488 @@
+//! Versioned JSON encoding of the syntax tree for tools outside Rust.
+//!
+//! A document is `{"version": 1, "statements": [...]}`. Every node is an
+//! object with a `kind`, its `token` (`type`, `literal` and `span`) and its
+//! children under named fields.
+
+use std::{error::Error, fmt};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ast::{Expression, Program, Statement},
+    token::{Span, Token, TokenType},
+};
+
+/// Bumped whenever the shape of the encoding changes.
+pub const FORMAT_VERSION: u64 = 1;
+
+// Operators the parser can produce, so decoded trees are ones it could have
+// built.
+const PREFIX_OPERATORS: [&str; 2] = ["!", "-"];
+const INFIX_OPERATORS: [&str; 12] = [
+    "+", "-", "*", "/", "%", "==", "!=", "<", ">", "<=", ">=", "..",
+];
+const ASSIGN_OPERATORS: [&str; 5] = ["=", "+=", "-=", "*=", "/="];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonError {
+    pub message: String,
+}
+
+impl JsonError {
+    fn new(message: String) -> JsonError {
+        JsonError { message }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid AST json: {}", self.message)
+    }
+}
+
+impl Error for JsonError {}
+
+pub fn to_json(program: &Program<Statement>) -> String {
+    let doc = json!({
+        "version": FORMAT_VERSION,
+        "statements": program.statements.iter().map(statement_to_value).collect::<Vec<Value>>(),
+    });
+    serde_json::to_string_pretty(&doc).expect("AST values always serialize")
+}
+
+pub fn from_json(src: &str) -> Result<Program<Statement>, JsonError> {
+    let doc: Value = serde_json::from_str(src).map_err(|err| JsonError::new(err.to_string()))?;
+    let doc = as_object(&doc, "document")?;
+
+    let version = doc
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| JsonError::new(String::from("missing `version`")))?;
+    if version != FORMAT_VERSION {
+        return Err(JsonError::new(format!(
+            "unsupported version {}, expected {}",
+            version, FORMAT_VERSION
+        )));
+    }
+
+    let statements = array_field(doc, "statements")?
+        .iter()
+        .map(statement_from_value)
+        .collect::<Result<Vec<Statement>, JsonError>>()?;
+    Ok(Program { statements })
+}
+
+fn token_to_value(token: &Token) -> Value {
+    json!({
+        "type": format!("{:?}", token.token_type),
+        "literal": token.literal,
+        "span": {
+            "line": token.span.line,
+            "column": token.span.column,
+            "len": token.span.len,
+        },
+    })
+}
+
+fn node(kind: &str, token: &Token, fields: Value) -> Value {
+    let mut obj = Map::new();
+    obj.insert(String::from("kind"), json!(kind));
+    obj.insert(String::from("token"), token_to_value(token));
+    if let Value::Object(fields) = fields {
+        obj.extend(fields);
+    }
+    Value::Object(obj)
+}
+
+fn statement_to_value(stmt: &Statement) -> Value {
+    match stmt {
+        Statement::LetStatement { token, name, value } => node(
+            "Let",
+            token,
+            json!({ "name": expression_to_value(name), "value": expression_to_value(value) }),
+        ),
+        Statement::ReturnStatement { token, value } => node(
+            "Return",
+            token,
+            json!({ "value": expression_to_value(value) }),
+        ),
+        Statement::ExpressionStatement { token, expression } => node(
+            "Expression",
+            token,
+            json!({ "expression": expression_to_value(expression) }),
+        ),
+        Statement::BlockStatement { token, statements } => node(
+            "Block",
+            token,
+            json!({ "statements": statements.iter().map(statement_to_value).collect::<Vec<Value>>() }),
+        ),
+        Statement::WhileStatement {
+            token,
+            condition,
+            body,
+        } => node(
+            "While",
+            token,
+            json!({ "condition": expression_to_value(condition), "body": statement_to_value(body) }),
+        ),
+        Statement::ForStatement {
+            token,
+            variable,
+            iterable,
+            body,
+        } => node(
+            "For",
+            token,
+            json!({
+                "variable": expression_to_value(variable),
+                "iterable": expression_to_value(iterable),
+                "body": statement_to_value(body),
+            }),
+        ),
+        Statement::BreakStatement { token } => node("Break", token, json!({})),
+        Statement::ContinueStatement { token } => node("Continue", token, json!({})),
+    }
+}
+
+fn expressions_to_value(exprs: &[Expression]) -> Value {
+    Value::Array(exprs.iter().map(expression_to_value).collect())
+}
+
+fn expression_to_value(expr: &Expression) -> Value {
+    match expr {
+        Expression::Identifier { token, value } => {
+            node("Identifier", token, json!({ "value": value }))
+        }
+        Expression::IntegerLiteral { token, value } => {
+            node("IntegerLiteral", token, json!({ "value": value }))
+        }
+        Expression::FloatLiteral { token, value } => {
+            node("FloatLiteral", token, json!({ "value": value }))
+        }
+        Expression::BooleanLiteral { token, value } => {
+            node("BooleanLiteral", token, json!({ "value": value }))
+        }
+        Expression::StringLiteral { token, value } => {
+            node("StringLiteral", token, json!({ "value": value }))
+        }
+        Expression::Prefix {
+            token,
+            operator,
+            right,
+        } => node(
+            "Prefix",
+            token,
+            json!({ "operator": operator, "right": expression_to_value(right) }),
+        ),
+        Expression::Infix {
+            token,
+            left,
+            operator,
+            right,
+        } => node(
+            "Infix",
+            token,
+            json!({
+                "left": expression_to_value(left),
+                "operator": operator,
+                "right": expression_to_value(right),
+            }),
+        ),
+        Expression::IfExpression {
+            token,
+            condition,
+            consequence,
+            alternative,
+        } => node(
+            "If",
+            token,
+            json!({
+                "condition": expression_to_value(condition),
+                "consequence": statement_to_value(consequence),
+                "alternative": alternative.as_ref().as_ref().map(statement_to_value),
+            }),
+        ),
+        Expression::FuncExpression {
+            token,
+            parameters,
+            defaults,
+            body,
+        } => node(
+            "Function",
+            token,
+            json!({
+                "parameters": expressions_to_value(parameters),
+                "defaults": defaults
+                    .iter()
+                    .map(|d| d.as_ref().map(expression_to_value))
+                    .collect::<Vec<Option<Value>>>(),
+                "body": statement_to_value(body),
+            }),
+        ),
+        Expression::CallExpression { token, func, args } => node(
+            "Call",
+            token,
+            json!({ "function": expression_to_value(func), "arguments": expressions_to_value(args) }),
+        ),
+        Expression::ArrayLiteral { token, elements } => node(
+            "Array",
+            token,
+            json!({ "elements": expressions_to_value(elements) }),
+        ),
+        Expression::IndexExpression { token, left, index } => node(
+            "Index",
+            token,
+            json!({ "left": expression_to_value(left), "index": expression_to_value(index) }),
+        ),
+        Expression::HashLiteral { token, pairs } => node(
+            "Hash",
+            token,
+            json!({
+                "pairs": pairs
+                    .iter()
+                    .map(|(k, v)| json!([expression_to_value(k), expression_to_value(v)]))
+                    .collect::<Vec<Value>>(),
+            }),
+        ),
+        Expression::Assign {
+            token,
+            name,
+            operator,
+            value,
+        } => node(
+            "Assign",
+            token,
+            json!({
+                "name": expression_to_value(name),
+                "operator": operator,
+                "value": expression_to_value(value),
+            }),
+        ),
+        Expression::NoExpression => json!({ "kind": "NoExpression" }),
+    }
+}
+
+fn as_object<'a>(value: &'a Value, what: &str) -> Result<&'a Map<String, Value>, JsonError> {
+    value
+        .as_object()
+        .ok_or_else(|| JsonError::new(format!("{} is not an object", what)))
+}
+
+fn field<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<&'a Value, JsonError> {
+    obj.get(name)
+        .ok_or_else(|| JsonError::new(format!("missing `{}`", name)))
+}
+
+fn str_field<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<&'a str, JsonError> {
+    field(obj, name)?
+        .as_str()
+        .ok_or_else(|| JsonError::new(format!("`{}` is not a string", name)))
+}
+
+fn usize_field(obj: &Map<String, Value>, name: &str) -> Result<usize, JsonError> {
+    field(obj, name)?
+        .as_u64()
+        .map(|n| n as usize)
+        .ok_or_else(|| JsonError::new(format!("`{}` is not an unsigned integer", name)))
+}
+
+fn array_field<'a>(obj: &'a Map<String, Value>, name: &str) -> Result<&'a Vec<Value>, JsonError> {
+    field(obj, name)?
+        .as_array()
+        .ok_or_else(|| JsonError::new(format!("`{}` is not an array", name)))
+}
+
+fn token_from_value(value: &Value) -> Result<Token, JsonError> {
+    let obj = as_object(value, "token")?;
+    let type_name = str_field(obj, "type")?;
+    let token_type = TokenType::from_name(type_name)
+        .ok_or_else(|| JsonError::new(format!("unknown token type `{}`", type_name)))?;
+    let span = as_object(field(obj, "span")?, "span")?;
+
+    Ok(Token {
+        token_type,
+        literal: str_field(obj, "literal")?.to_string(),
+        span: Span {
+            line: usize_field(span, "line")?,
+            column: usize_field(span, "column")?,
+            len: usize_field(span, "len")?,
+        },
+    })
+}
+
+fn statement_from_value(value: &Value) -> Result<Statement, JsonError> {
+    let obj = as_object(value, "statement")?;
+    let token = token_from_value(field(obj, "token")?)?;
+    let expr = |name: &str| expression_from_value(field(obj, name)?);
+
+    Ok(match str_field(obj, "kind")? {
+        "Let" => Statement::LetStatement {
+            token,
+            name: expr("name")?,
+            value: expr("value")?,
+        },
+        "Return" => Statement::ReturnStatement {
+            token,
+            value: expr("value")?,
+        },
+        "Expression" => Statement::ExpressionStatement {
+            token,
+            expression: expr("expression")?,
+        },
+        "Block" => Statement::BlockStatement {
+            token,
+            statements: array_field(obj, "statements")?
+                .iter()
+                .map(statement_from_value)
+                .collect::<Result<Vec<Statement>, JsonError>>()?,
+        },
+        "While" => Statement::WhileStatement {
+            token,
+            condition: expr("condition")?,
+            body: Box::new(statement_from_value(field(obj, "body")?)?),
+        },
+        "For" => Statement::ForStatement {
+            token,
+            variable: expr("variable")?,
+            iterable: expr("iterable")?,
+            body: Box::new(statement_from_value(field(obj, "body")?)?),
+        },
+        "Break" => Statement::BreakStatement { token },
+        "Continue" => Statement::ContinueStatement { token },
+        kind => return Err(JsonError::new(format!("unknown statement kind `{}`", kind))),
+    })
+}
+
+fn expressions_from_value(values: &[Value]) -> Result<Vec<Expression>, JsonError> {
+    values.iter().map(expression_from_value).collect()
+}
+
+fn expression_from_value(value: &Value) -> Result<Expression, JsonError> {
+    let obj = as_object(value, "expression")?;
+    let kind = str_field(obj, "kind")?;
+    let token = token_from_value(field(obj, "token")?)?;
+    let expr = |name: &str| -> Result<Box<Expression>, JsonError> {
+        Ok(Box::new(expression_from_value(field(obj, name)?)?))
+    };
+    let string =
+        |name: &str| -> Result<String, JsonError> { Ok(str_field(obj, name)?.to_string()) };
+    let operator = |allowed: &[&str]| -> Result<String, JsonError> {
+        match str_field(obj, "operator")? {
+            op if allowed.contains(&op) => Ok(op.to_string()),
+            op => Err(JsonError::new(format!(
+                "unknown {} operator `{}`",
+                kind.to_lowercase(),
+                op
+            ))),
+        }
+    };
+
+    Ok(match kind {
+        "Identifier" => Expression::Identifier {
+            token,
+            value: string("value")?,
+        },
+        "IntegerLiteral" => Expression::IntegerLiteral {
+            token,
+            value: field(obj, "value")?
+                .as_i64()
+                .ok_or_else(|| JsonError::new(String::from("`value` is not an integer")))?,
+        },
+        "FloatLiteral" => Expression::FloatLiteral {
+            token,
+            value: field(obj, "value")?
+                .as_f64()
+                .ok_or_else(|| JsonError::new(String::from("`value` is not a number")))?,
+        },
+        "BooleanLiteral" => Expression::BooleanLiteral {
+            token,
+            value: field(obj, "value")?
+                .as_bool()
+                .ok_or_else(|| JsonError::new(String::from("`value` is not a boolean")))?,
+        },
+        "StringLiteral" => Expression::StringLiteral {
+            token,
+            value: string("value")?,
+        },
+        "Prefix" => Expression::Prefix {
+            token,
+            operator: operator(&PREFIX_OPERATORS)?,
+            right: expr("right")?,
+        },
+        "Infix" => Expression::Infix {
+            token,
+            left: expr("left")?,
+            operator: operator(&INFIX_OPERATORS)?,
+            right: expr("right")?,
+        },
+        "If" => Expression::IfExpression {
+            token,
+            condition: expr("condition")?,
+            consequence: Box::new(statement_from_value(field(obj, "consequence")?)?),
+            alternative: Box::new(match field(obj, "alternative")? {
+                Value::Null => None,
+                alt => Some(statement_from_value(alt)?),
+            }),
+        },
+        "Function" => Expression::FuncExpression {
+            token,
+            parameters: expressions_from_value(array_field(obj, "parameters")?)?,
+            defaults: array_field(obj, "defaults")?
+                .iter()
+                .map(|d| match d {
+                    Value::Null => Ok(None),
+                    d => expression_from_value(d).map(Some),
+                })
+                .collect::<Result<Vec<Option<Expression>>, JsonError>>()?,
+            body: Box::new(statement_from_value(field(obj, "body")?)?),
+        },
+        "Call" => Expression::CallExpression {
+            token,
+            func: expr("function")?,
+            args: expressions_from_value(array_field(obj, "arguments")?)?,
+        },
+        "Array" => Expression::ArrayLiteral {
+            token,
+            elements: expressions_from_value(array_field(obj, "elements")?)?,
+        },
+        "Index" => Expression::IndexExpression {
+            token,
+            left: expr("left")?,
+            index: expr("index")?,
+        },
+        "Hash" => Expression::HashLiteral {
+            token,
+            pairs: array_field(obj, "pairs")?
+                .iter()
+                .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                    Some([key, value]) => {
+                        Ok((expression_from_value(key)?, expression_from_value(value)?))
+                    }
+                    _ => Err(JsonError::new(String::from(
+                        "hash pair is not a [key, value] array",
+                    ))),
+                })
+                .collect::<Result<Vec<(Expression, Expression)>, JsonError>>()?,
+        },
+        "Assign" => Expression::Assign {
+            token,
+            name: match expr("name")? {
+                name if matches!(*name, Expression::Identifier { .. }) => name,
+                _ => {
+                    return Err(JsonError::new(String::from(
+                        "assignment target is not an identifier",
+                    )))
+                }
+            },
+            operator: operator(&ASSIGN_OPERATORS)?,
+            value: expr("value")?,
+        },
+        kind => {
+            return Err(JsonError::new(format!(
+                "unknown expression kind `{}`",
+                kind
+            )))
+        }
+    })
+}
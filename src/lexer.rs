@@ -1,4 +1,9 @@
-use crate::token::{Token, TokenType};
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::{
+    parser::ParseError,
+    token::{Span, Token, TokenType},
+};
 
 #[derive(Debug)]
 pub struct Lexer {
@@ -6,6 +11,10 @@ pub struct Lexer {
     position: usize,
     read_position: usize,
     ch: char,
+    line: usize,
+    column: usize,
+    // Problems that do not fit in a token, such as an unterminated comment.
+    errors: Vec<ParseError>,
 }
 
 impl Lexer {
@@ -15,32 +24,56 @@ impl Lexer {
             position: 0,
             read_position: 0,
             ch: '\0',
+            line: 1,
+            column: 0,
+            errors: vec![],
         };
 
         l.read_char();
-        return l;
+        l
     }
 
     fn read_char(&mut self) {
+        if self.ch == '\n' {
+            self.line += 1;
+            self.column = 0;
+        }
+        self.column += 1;
         self.ch = *self.input.get(self.read_position).unwrap_or(&'\0');
         self.position = self.read_position;
         self.read_position += 1;
     }
 
+    /// Returns the errors found since the last call, in input order.
+    pub fn take_errors(&mut self) -> Vec<ParseError> {
+        std::mem::take(&mut self.errors)
+    }
+
     pub fn next_token(&mut self) -> Token {
-        self.skip_white_space();
+        if let Some(span) = self.skip_white_space() {
+            self.errors.push(ParseError::UnterminatedComment { span });
+        }
+        let (line, column, start) = (self.line, self.column, self.position);
+
+        let mut t = self.read_token();
+        t.span = Span {
+            line,
+            column,
+            len: (self.position - start).max(1),
+        };
+        t
+    }
+
+    fn read_token(&mut self) -> Token {
         let mut literal = self.ch.to_string();
-        let t = Token {
-            token_type: match self.ch {
+        let t = Token::new(
+            match self.ch {
                 '=' => {
                     if self.peek_char() == '=' {
                         self.read_char();
                         literal.push(self.ch);
                         self.read_char();
-                        return Token {
-                            token_type: TokenType::EQ,
-                            literal,
-                        };
+                        return Token::new(TokenType::EQ, literal);
                     }
                     TokenType::ASSIGN
                 }
@@ -49,41 +82,99 @@ impl Lexer {
                 '(' => TokenType::LPAREN,
                 ')' => TokenType::RPAREN,
                 ',' => TokenType::COMMA,
-                '+' => TokenType::PLUS,
-                '-' => TokenType::MINUS,
+                '+' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::PLUSASSIGN, literal);
+                    }
+                    TokenType::PLUS
+                }
+                '-' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::MINUSASSIGN, literal);
+                    }
+                    TokenType::MINUS
+                }
                 '{' => TokenType::LBRACE,
                 '}' => TokenType::RBRACE,
+                '[' => TokenType::LBRACKET,
+                ']' => TokenType::RBRACKET,
                 '!' => {
                     if self.peek_char() == '=' {
                         self.read_char();
                         literal.push(self.ch);
                         self.read_char();
-                        return Token {
-                            token_type: TokenType::NOTEQ,
-                            literal,
-                        };
+                        return Token::new(TokenType::NOTEQ, literal);
                     }
                     TokenType::BANG
                 }
-                '*' => TokenType::ASTERISK,
-                '<' => TokenType::LT,
-                '>' => TokenType::GT,
-                '/' => TokenType::SLASH,
+                '*' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::ASTERISKASSIGN, literal);
+                    }
+                    TokenType::ASTERISK
+                }
+                '%' => TokenType::PERCENT,
+                '<' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::LTEQ, literal);
+                    }
+                    TokenType::LT
+                }
+                '>' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::GTEQ, literal);
+                    }
+                    TokenType::GT
+                }
+                '/' => {
+                    if self.peek_char() == '=' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::SLASHASSIGN, literal);
+                    }
+                    TokenType::SLASH
+                }
+                '.' => {
+                    if self.peek_char() == '.' {
+                        self.read_char();
+                        literal.push(self.ch);
+                        self.read_char();
+                        return Token::new(TokenType::DOTDOT, literal);
+                    }
+                    TokenType::ILLEGAL
+                }
+                '"' => match self.read_string() {
+                    Some(s) => {
+                        literal = s;
+                        TokenType::STRING
+                    }
+                    None => TokenType::ILLEGAL,
+                },
                 '\0' => TokenType::EOF,
                 ch => {
                     let t_type: TokenType;
                     if Lexer::is_letter(ch) {
                         literal = self.read_identifier().into_iter().collect();
-                        return Token {
-                            token_type: Token::lookup_ident(&literal),
-                            literal,
-                        };
+                        return Token::new(Token::lookup_ident(&literal), literal);
                     } else if Lexer::is_digit(ch) {
-                        literal = self.read_number().into_iter().collect();
-                        return Token {
-                            token_type: TokenType::INT,
-                            literal,
-                        };
+                        let (t_type, number) = self.read_number();
+                        return Token::new(t_type, number.into_iter().collect());
                     } else {
                         t_type = TokenType::ILLEGAL
                     }
@@ -91,42 +182,153 @@ impl Lexer {
                 }
             },
             literal,
-        };
+        );
 
         self.read_char();
-        return t;
+        t
     }
 
     fn read_identifier(&mut self) -> Vec<char> {
         let pos = self.position;
-        while Lexer::is_letter(self.ch) {
+        while Lexer::is_ident_char(self.ch) {
             self.read_char();
         }
 
         self.input[pos..self.position].into()
     }
 
+    // Identifiers follow Unicode XID, with `_` also allowed to start one.
     fn is_letter(ch: char) -> bool {
-        'a' <= ch && ch <= 'z' || 'A' <= ch && ch <= 'Z' || ch == '_'
+        ch == '_' || is_xid_start(ch)
+    }
+
+    fn is_ident_char(ch: char) -> bool {
+        is_xid_continue(ch)
     }
 
     fn is_digit(ch: char) -> bool {
-        '0' <= ch && ch <= '9'
+        ch.is_ascii_digit()
     }
 
-    fn skip_white_space(&mut self) {
-        while self.ch == ' ' || self.ch == '\t' || self.ch == '\n' || self.ch == '\r' {
-            self.read_char();
+    // Skips comments along with the white space. Returns the span of a block
+    // comment that runs to the end of the input.
+    fn skip_white_space(&mut self) -> Option<Span> {
+        loop {
+            match (self.ch, self.peek_char()) {
+                (' ' | '\t' | '\n' | '\r', _) => self.read_char(),
+                ('/', '/') => {
+                    while self.ch != '\n' && self.ch != '\0' {
+                        self.read_char();
+                    }
+                }
+                ('/', '*') => {
+                    let span = Span {
+                        line: self.line,
+                        column: self.column,
+                        len: 2,
+                    };
+                    if !self.skip_block_comment() {
+                        return Some(span);
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    // Block comments nest, so `/* a /* b */ c */` is a single comment.
+    fn skip_block_comment(&mut self) -> bool {
+        let mut depth = 0;
+        loop {
+            match (self.ch, self.peek_char()) {
+                ('/', '*') => {
+                    depth += 1;
+                    self.read_char();
+                    self.read_char();
+                }
+                ('*', '/') => {
+                    depth -= 1;
+                    self.read_char();
+                    self.read_char();
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+                ('\0', _) => return false,
+                _ => self.read_char(),
+            }
         }
     }
 
-    fn read_number(&mut self) -> Vec<char> {
+    // A fraction needs a digit after the dot, which keeps `0..10` a range.
+    fn read_number(&mut self) -> (TokenType, Vec<char>) {
         let pos = self.position;
-        while Lexer::is_digit(self.ch) {
+        let mut t_type = TokenType::INT;
+
+        // Everything alphanumeric belongs to a prefixed literal, so that the
+        // parser can point at a bad digit such as the `2` in `0b102`.
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'o' | 'b') {
             self.read_char();
+            self.read_char();
+            while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
+                self.read_char();
+            }
+            return (t_type, self.input[pos..self.position].into());
         }
 
-        self.input[pos..self.position].into()
+        self.read_digits();
+
+        if self.ch == '.' && Lexer::is_digit(self.peek_char()) {
+            t_type = TokenType::FLOAT;
+            self.read_char();
+            self.read_digits();
+        }
+
+        if self.ch == 'e' || self.ch == 'E' {
+            let sign = matches!(self.peek_char(), '+' | '-') as usize;
+            let after = self.input.get(self.read_position + sign).copied();
+            if after.is_some_and(Lexer::is_digit) {
+                t_type = TokenType::FLOAT;
+                (0..=sign).for_each(|_| self.read_char());
+                self.read_digits();
+            }
+        }
+
+        (t_type, self.input[pos..self.position].into())
+    }
+
+    fn read_digits(&mut self) {
+        while Lexer::is_digit(self.ch) || self.ch == '_' {
+            self.read_char();
+        }
+    }
+
+    // Returns None when the input ends before the closing quote.
+    fn read_string(&mut self) -> Option<String> {
+        let mut s = String::new();
+        loop {
+            self.read_char();
+            match self.ch {
+                '"' => return Some(s),
+                '\0' => return None,
+                '\\' => {
+                    self.read_char();
+                    match self.ch {
+                        'n' => s.push('\n'),
+                        't' => s.push('\t'),
+                        'r' => s.push('\r'),
+                        '"' => s.push('"'),
+                        '\\' => s.push('\\'),
+                        '\0' => return None,
+                        ch => {
+                            s.push('\\');
+                            s.push(ch);
+                        }
+                    }
+                }
+                ch => s.push(ch),
+            }
+        }
     }
 
     fn peek_char(&self) -> char {
@@ -1,7 +1,15 @@
 pub mod ast;
+pub mod builtins;
+pub mod cli;
+pub mod compiler;
+pub mod diagnostics;
 pub mod environment;
 pub mod evaluator;
+pub mod interpreter;
+pub mod json;
 pub mod lexer;
 pub mod object;
 pub mod parser;
+pub mod repl;
 pub mod token;
+pub mod vm;
@@ -1,15 +1,29 @@
-use std::env;
+use std::{env, process};
 
-mod ast;
-mod environment;
-mod evaluator;
-pub mod lexer;
-mod object;
-mod parser;
-mod repl;
-mod token;
+use d_lang::cli::{self, Command, Options, Source, EXIT_USAGE};
 
 fn main() {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
+
+    if let Command::Run(Options {
+        source: Source::Repl,
+        banner: true,
+        ..
+    }) = command
+    {
+        print_banner();
+    }
+
+    process::exit(cli::run(command));
+}
+
+fn print_banner() {
     println!("====================");
     println!("Welcome to D_Lang!!!");
     println!("====================");
@@ -42,15 +56,5 @@ fn main() {
                   .[[\OOOOOO/[.                  
         "
     );
-
-    let filename = match env::args().collect::<Vec<String>>().get(1) {
-        Some(f) => f.clone(),
-        None => "".to_string(),
-    };
-
-    if filename == "" {
-        repl::run();
-    } else {
-        repl::run_file(filename);
-    }
+    println!("Type :help for session commands.");
 }
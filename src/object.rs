@@ -1,37 +1,169 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt, rc::Rc};
+
 use macros::sf;
 
-use crate::ast::{Expression, Statement};
+use crate::{
+    ast::{Expression, Node, Statement},
+    compiler::CompiledFunction,
+    environment::Environment,
+    evaluator::RuntimeError,
+};
 
 #[derive(Debug, Clone)]
 pub enum Object {
     Integer {
         value: i64,
     },
+    Float {
+        value: f64,
+    },
     Boolean {
         value: bool,
     },
+    String {
+        value: String,
+    },
+    Array {
+        elements: Vec<Object>,
+    },
+    Hash {
+        pairs: BTreeMap<HashKey, (Object, Object)>,
+    },
+    // The integers from `start` up to but not including `end`.
+    Range {
+        start: i64,
+        end: i64,
+    },
     Return {
         value: Box<Object>,
     },
-    Error {
-        message: String,
-    },
+    // Like `Return`, these only travel up to the enclosing loop.
+    Break {},
+    Continue {},
     Function {
         parameters: Vec<Expression>,
+        defaults: Vec<Option<Expression>>,
         body: Box<Statement>,
+        env: Rc<RefCell<Environment>>,
+    },
+    Closure {
+        function: Rc<CompiledFunction>,
+        env: Rc<RefCell<Environment>>,
+    },
+    Builtin {
+        name: String,
+        func: BuiltinFunction,
     },
     Null {},
 }
 
+/// A function implemented in Rust. Closures may capture host state.
+#[derive(Clone)]
+pub struct BuiltinFunction(Rc<dyn Fn(Vec<Object>) -> Result<Object, RuntimeError>>);
+
+impl BuiltinFunction {
+    pub fn new(func: impl Fn(Vec<Object>) -> Result<Object, RuntimeError> + 'static) -> Self {
+        BuiltinFunction(Rc::new(func))
+    }
+
+    pub fn call(&self, args: Vec<Object>) -> Result<Object, RuntimeError> {
+        (self.0)(args)
+    }
+}
+
+impl fmt::Debug for BuiltinFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BuiltinFunction")
+    }
+}
+
 impl Object {
     pub fn get_type(&self) -> String {
         match self {
             Object::Integer { .. } => sf!("INTEGER"),
+            Object::Float { .. } => sf!("FLOAT"),
             Object::Boolean { .. } => sf!("BOOLEAN"),
+            Object::String { .. } => sf!("STRING"),
+            Object::Array { .. } => sf!("ARRAY"),
+            Object::Hash { .. } => sf!("HASH"),
+            Object::Range { .. } => sf!("RANGE"),
             Object::Return { .. } => sf!("RETURN"),
-            Object::Error { .. } => sf!("ERROR"),
-            Object::Function { .. } => sf!("FUNCTION"),
+            Object::Break {} => sf!("BREAK"),
+            Object::Continue {} => sf!("CONTINUE"),
+            Object::Function { .. } | Object::Closure { .. } => sf!("FUNCTION"),
+            Object::Builtin { .. } => sf!("BUILTIN"),
             Object::Null {} => sf!("NULL"),
         }
     }
+
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match self {
+            Object::Integer { value } => Some(HashKey::Integer(*value)),
+            Object::Boolean { value } => Some(HashKey::Boolean(*value)),
+            Object::String { value } => Some(HashKey::String(value.clone())),
+            _ => None,
+        }
+    }
+
+    /// Like `to_string`, but strings are quoted and escaped the way they are
+    /// written in source, so `"1"` and `1` read apart.
+    pub fn inspect(&self) -> String {
+        match self {
+            Object::String { value } => format!("{:?}", value),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Object::Integer { value } => write!(f, "{}", value),
+            // Debug keeps the `.0` on whole floats so they read apart from integers.
+            Object::Float { value } => write!(f, "{:?}", value),
+            Object::Boolean { value } => write!(f, "{}", value),
+            Object::String { value } => write!(f, "{}", value),
+            Object::Array { elements } => {
+                let elements: Vec<String> = elements.iter().map(|e| e.inspect()).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Object::Hash { pairs } => {
+                let pairs: Vec<String> = pairs
+                    .values()
+                    .map(|(key, value)| format!("{}: {}", key.inspect(), value.inspect()))
+                    .collect();
+                write!(f, "{{{}}}", pairs.join(", "))
+            }
+            Object::Range { start, end } => write!(f, "{}..{}", start, end),
+            Object::Return { value } => write!(f, "{}", value),
+            Object::Break {} => write!(f, "break"),
+            Object::Continue {} => write!(f, "continue"),
+            Object::Function {
+                parameters,
+                defaults,
+                body,
+                ..
+            } => {
+                let parameters: Vec<String> = parameters
+                    .iter()
+                    .zip(defaults)
+                    .map(|(p, default)| match default {
+                        Some(default) => format!("{} = {}", p.to_string(), default.to_string()),
+                        None => p.to_string(),
+                    })
+                    .collect();
+                write!(f, "fn({}) {}", parameters.join(", "), body.to_string())
+            }
+            Object::Closure { function, .. } => write!(f, "{}", function.source),
+            Object::Builtin { name, .. } => write!(f, "builtin {}", name),
+            Object::Null {} => write!(f, "null"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HashKey {
+    Integer(i64),
+    Boolean(bool),
+    String(String),
 }
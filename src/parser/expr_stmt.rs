@@ -1,16 +1,18 @@
+use std::num::IntErrorKind;
+
 use crate::{
-    ast::{Expression, Statement},
-    token::{Token, TokenType},
+    ast::{Expression, Node, Statement},
+    token::{Span, Token, TokenType},
 };
 
-use super::{precedence::Precedence, Parser};
+use super::{precedence::Precedence, ParseError, Parser};
 
 impl Parser {
     pub fn parse_expression_statement(&mut self) -> Option<Statement> {
         let mut stmt = Statement::new(self.curr_token.token_type);
+        stmt.set_token(self.curr_token.clone());
 
         stmt.set_expression(self.parse_expression(Precedence::LOWEST));
-        stmt.set_expression_literal();
 
         if self.peek_token_is(TokenType::SEMICOLON) {
             self.next_token();
@@ -25,7 +27,7 @@ impl Parser {
         let prefix = match prefix_option {
             Some(prefix) => prefix,
             None => {
-                self.no_prefix_parse_fn_error(self.curr_token.token_type);
+                self.no_prefix_parse_fn_error();
                 return Expression::NoExpression;
             }
         };
@@ -49,6 +51,24 @@ impl Parser {
     }
 
     pub fn parse_prefix_expression(&mut self) -> Expression {
+        // The magnitude of i64::MIN is one more than i64::MAX, so it can only
+        // be written negated and is read together with its `-`.
+        if self.curr_token_is(TokenType::MINUS)
+            && self.peek_token_is(TokenType::INT)
+            && integer_magnitude(&self.peek_token.literal) == Some(i64::MIN.unsigned_abs())
+        {
+            let minus = self.curr_token.clone();
+            self.next_token();
+            return Expression::IntegerLiteral {
+                token: Token {
+                    token_type: TokenType::INT,
+                    literal: format!("-{}", self.curr_token.literal),
+                    span: minus.span.to(self.curr_token.span),
+                },
+                value: i64::MIN,
+            };
+        }
+
         Expression::Prefix {
             token: self.curr_token.clone(),
             operator: self.curr_token.literal.clone(),
@@ -73,36 +93,122 @@ impl Parser {
         }
     }
 
+    // Assignment is right associative, so `a = b = 1` assigns 1 to both.
+    pub fn parse_assign_expression(&mut self, left_expr: &Expression) -> Expression {
+        self.next_token();
+        let token = self.curr_token.clone();
+
+        if !matches!(left_expr, Expression::Identifier { .. }) {
+            self.errors.push(ParseError::InvalidAssignmentTarget {
+                target: left_expr.to_string(),
+                span: left_expr.span(),
+            });
+        }
+
+        self.next_token();
+        let value = self.parse_expression(Precedence::LOWEST);
+        Expression::Assign {
+            operator: token.literal.clone(),
+            token,
+            name: Box::new(left_expr.clone()),
+            value: Box::new(value),
+        }
+    }
+
     pub fn parse_identifier(&mut self) -> Expression {
         Expression::Identifier {
-            token: Token {
-                token_type: TokenType::IDENT,
-                literal: self.curr_token.literal.clone(),
-            },
+            token: self.curr_token.clone(),
             value: self.curr_token.literal.clone(),
         }
     }
 
     pub fn parse_integer_literal(&mut self) -> Expression {
-        Expression::IntegerLiteral {
-            token: Token {
-                token_type: TokenType::INT,
-                literal: self.curr_token.literal.clone(),
+        let literal = self.curr_token.literal.clone();
+        let span = self.curr_token.span;
+        let (radix, prefix) = radix_and_prefix(&literal);
+
+        let bad_digit = literal
+            .char_indices()
+            .skip(prefix)
+            .find(|(_, ch)| *ch != '_' && !ch.is_digit(radix));
+        if let Some((idx, digit)) = bad_digit {
+            self.errors.push(ParseError::InvalidDigit {
+                digit,
+                radix,
+                span: Span {
+                    column: span.column + idx,
+                    len: 1,
+                    ..span
+                },
+            });
+            return Expression::NoExpression;
+        }
+        if let Some(idx) = misplaced_separator(&literal[prefix..], radix) {
+            self.errors.push(ParseError::MisplacedSeparator {
+                span: Span {
+                    column: span.column + prefix + idx,
+                    len: 1,
+                    ..span
+                },
+            });
+            return Expression::NoExpression;
+        }
+
+        let digits: String = literal[prefix..].chars().filter(|ch| *ch != '_').collect();
+        match i64::from_str_radix(&digits, radix) {
+            Ok(value) => Expression::IntegerLiteral {
+                token: self.curr_token.clone(),
+                value,
+            },
+            Err(err) => {
+                self.errors.push(match err.kind() {
+                    IntErrorKind::PosOverflow => ParseError::NumberOutOfRange { literal, span },
+                    _ => ParseError::InvalidInteger { literal, span },
+                });
+                Expression::NoExpression
+            }
+        }
+    }
+
+    // The lexer only hands out well-formed floats, so the one failure left is
+    // a literal too large for an f64.
+    pub fn parse_float_literal(&mut self) -> Expression {
+        if let Some(idx) = misplaced_separator(&self.curr_token.literal, 10) {
+            self.errors.push(ParseError::MisplacedSeparator {
+                span: Span {
+                    column: self.curr_token.span.column + idx,
+                    len: 1,
+                    ..self.curr_token.span
+                },
+            });
+            return Expression::NoExpression;
+        }
+        match self.curr_token.literal.replace('_', "").parse::<f64>() {
+            Ok(value) if value.is_finite() => Expression::FloatLiteral {
+                token: self.curr_token.clone(),
+                value,
             },
-            value: self.curr_token.literal.clone().parse().expect(&format!(
-                "Failed to parse int, got={}",
-                self.curr_token.literal.clone(),
-            )),
+            _ => {
+                self.errors.push(ParseError::NumberOutOfRange {
+                    literal: self.curr_token.literal.clone(),
+                    span: self.curr_token.span,
+                });
+                Expression::NoExpression
+            }
+        }
+    }
+
+    pub fn parse_string_literal(&mut self) -> Expression {
+        Expression::StringLiteral {
+            token: self.curr_token.clone(),
+            value: self.curr_token.literal.clone(),
         }
     }
 
     pub fn parse_boolean(&mut self) -> Expression {
         Expression::BooleanLiteral {
             token: self.curr_token.clone(),
-            value: self.curr_token.literal.clone().parse().expect(&format!(
-                "Failed to parse int, got={}",
-                self.curr_token.literal.clone(),
-            )),
+            value: self.curr_token_is(TokenType::TRUE),
         }
     }
 
@@ -111,7 +217,7 @@ impl Parser {
         let expr = self.parse_expression(Precedence::LOWEST);
 
         if !self.expect_peek(TokenType::RPAREN) {
-            panic!("Error: no RPAREN found in grouped expression");
+            return Expression::NoExpression;
         }
 
         expr
@@ -124,10 +230,13 @@ impl Parser {
         let cond = self.parse_expression(Precedence::LOWEST);
 
         if !self.expect_peek(TokenType::LBRACE) {
-            panic!("LBRACE Expected after condition!!!");
+            return Expression::NoExpression;
         }
 
-        let consequence = self.parse_block_statement().unwrap();
+        let consequence = match self.parse_block_statement() {
+            Some(consequence) => consequence,
+            None => return Expression::NoExpression,
+        };
         let mut alternative = Box::new(None);
 
         if self.peek_token_is(TokenType::ELSE) {
@@ -136,11 +245,12 @@ impl Parser {
             if self.peek_token_is(TokenType::IF) {
                 self.next_token();
                 let mut stmt = Statement::new(TokenType::IF);
+                stmt.set_token(self.curr_token.clone());
                 stmt.set_expression(self.parse_if_expression());
                 alternative = Box::new(Some(stmt));
             } else {
                 if !self.expect_peek(TokenType::LBRACE) {
-                    panic!("LBRACE Expected after else!!!");
+                    return Expression::NoExpression;
                 }
                 alternative = Box::new(self.parse_block_statement());
             }
@@ -158,63 +268,89 @@ impl Parser {
         let curr_token = self.curr_token.clone();
 
         if !self.expect_peek(TokenType::LPAREN) {
-            panic!("LPAREN Expected after fn!!!");
+            return Expression::NoExpression;
         }
 
-        let parameters = self.parse_func_parameters();
+        let errors = self.errors.len();
+        let (parameters, defaults) = self.parse_func_parameters();
+        if self.errors.len() > errors {
+            return Expression::NoExpression;
+        }
 
         if !self.expect_peek(TokenType::LBRACE) {
-            panic!("LBRACE Expected after parameters!!!");
+            return Expression::NoExpression;
         }
 
-        let body = self.parse_block_statement().unwrap();
+        // Loops around the function do not continue into its body.
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.parse_block_statement();
+        self.loop_depth = loop_depth;
+        let body = match body {
+            Some(body) => body,
+            None => return Expression::NoExpression,
+        };
 
         Expression::FuncExpression {
             token: curr_token,
             parameters,
+            defaults,
             body: Box::new(body),
         }
     }
 
-    fn parse_func_parameters(&mut self) -> Vec<Expression> {
+    fn parse_func_parameters(&mut self) -> (Vec<Expression>, Vec<Option<Expression>>) {
         let mut identifiers = vec![];
+        let mut defaults = vec![];
 
         if self.peek_token_is(TokenType::RPAREN) {
             self.next_token();
-            return identifiers;
+            return (identifiers, defaults);
         }
 
-        self.next_token();
-
-        let ident = Expression::Identifier {
-            token: self.curr_token.clone(),
-            value: self.curr_token.literal.clone(),
-        };
-
-        identifiers.push(ident);
+        loop {
+            if !self.expect_peek(TokenType::IDENT) {
+                return (vec![], vec![]);
+            }
 
-        while self.peek_token_is(TokenType::COMMA) {
-            self.next_token();
-            self.next_token();
             let ident = Expression::Identifier {
                 token: self.curr_token.clone(),
                 value: self.curr_token.literal.clone(),
             };
 
+            let default = if self.peek_token_is(TokenType::ASSIGN) {
+                self.next_token();
+                self.next_token();
+                Some(self.parse_expression(Precedence::LOWEST))
+            } else {
+                if let Some(Some(_)) = defaults.last() {
+                    self.errors.push(ParseError::RequiredAfterDefault {
+                        name: ident.to_string(),
+                        span: ident.span(),
+                    });
+                }
+                None
+            };
+
             identifiers.push(ident);
+            defaults.push(default);
+
+            if !self.peek_token_is(TokenType::COMMA) {
+                break;
+            }
+            self.next_token();
         }
 
         if !self.expect_peek(TokenType::RPAREN) {
-            panic!("RPAREN Expected after LPAREN!!!")
+            return (vec![], vec![]);
         }
 
-        identifiers
+        (identifiers, defaults)
     }
 
     pub fn parse_call_expression(&mut self, left_expr: &Expression) -> Expression {
         self.next_token();
         let curr_token = self.curr_token.clone();
-        let args = self.parse_call_args();
+        let args = self.parse_expression_list(TokenType::RPAREN);
         Expression::CallExpression {
             token: curr_token,
             func: Box::new(left_expr.clone()),
@@ -222,27 +358,117 @@ impl Parser {
         }
     }
 
-    fn parse_call_args(&mut self) -> Vec<Expression> {
-        let mut args = vec![];
+    pub fn parse_array_literal(&mut self) -> Expression {
+        let curr_token = self.curr_token.clone();
+        let elements = self.parse_expression_list(TokenType::RBRACKET);
+        Expression::ArrayLiteral {
+            token: curr_token,
+            elements,
+        }
+    }
 
-        if self.peek_token_is(TokenType::RPAREN) {
+    pub fn parse_hash_literal(&mut self) -> Expression {
+        let curr_token = self.curr_token.clone();
+        let mut pairs = vec![];
+
+        while !self.peek_token_is(TokenType::RBRACE) {
+            self.next_token();
+            let key = self.parse_expression(Precedence::LOWEST);
+
+            if !self.expect_peek(TokenType::COLON) {
+                return Expression::NoExpression;
+            }
+
+            self.next_token();
+            let value = self.parse_expression(Precedence::LOWEST);
+            pairs.push((key, value));
+
+            if !self.peek_token_is(TokenType::RBRACE) && !self.expect_peek(TokenType::COMMA) {
+                return Expression::NoExpression;
+            }
+        }
+
+        if !self.expect_peek(TokenType::RBRACE) {
+            return Expression::NoExpression;
+        }
+
+        Expression::HashLiteral {
+            token: curr_token,
+            pairs,
+        }
+    }
+
+    pub fn parse_index_expression(&mut self, left_expr: &Expression) -> Expression {
+        self.next_token();
+        let curr_token = self.curr_token.clone();
+
+        self.next_token();
+        let index = self.parse_expression(Precedence::LOWEST);
+
+        if !self.expect_peek(TokenType::RBRACKET) {
+            return Expression::NoExpression;
+        }
+
+        Expression::IndexExpression {
+            token: curr_token,
+            left: Box::new(left_expr.clone()),
+            index: Box::new(index),
+        }
+    }
+
+    fn parse_expression_list(&mut self, end: TokenType) -> Vec<Expression> {
+        let mut list = vec![];
+
+        if self.peek_token_is(end) {
             self.next_token();
-            return args;
+            return list;
         }
 
         self.next_token();
-        args.push(self.parse_expression(Precedence::LOWEST));
+        list.push(self.parse_expression(Precedence::LOWEST));
 
         while self.peek_token_is(TokenType::COMMA) {
             self.next_token();
             self.next_token();
-            args.push(self.parse_expression(Precedence::LOWEST));
+            list.push(self.parse_expression(Precedence::LOWEST));
         }
 
-        if !self.expect_peek(TokenType::RPAREN) {
-            panic!("RPAREN Expected after LPAREN!!!")
+        if !self.expect_peek(end) {
+            return vec![];
         }
 
-        args
+        list
+    }
+}
+
+fn radix_and_prefix(literal: &str) -> (u32, usize) {
+    match literal.get(..2) {
+        Some("0x") => (16, 2),
+        Some("0o") => (8, 2),
+        Some("0b") => (2, 2),
+        _ => (10, 0),
+    }
+}
+
+// Value of a well-formed integer literal, even one too large for an i64.
+fn integer_magnitude(literal: &str) -> Option<u64> {
+    let (radix, prefix) = radix_and_prefix(literal);
+    if misplaced_separator(&literal[prefix..], radix).is_some() {
+        return None;
     }
+    let digits: String = literal[prefix..].chars().filter(|ch| *ch != '_').collect();
+    u64::from_str_radix(&digits, radix).ok()
+}
+
+// Index of the first `_` that is not between two digits, as in `1_`, `1__0`
+// or `1_.5`.
+fn misplaced_separator(digits: &str, radix: u32) -> Option<usize> {
+    let chars: Vec<char> = digits.chars().collect();
+    let is_digit = |idx: Option<usize>| {
+        idx.and_then(|idx| chars.get(idx))
+            .is_some_and(|ch| ch.is_digit(radix))
+    };
+    (0..chars.len()).find(|idx| {
+        chars[*idx] == '_' && !(is_digit(idx.checked_sub(1)) && is_digit(Some(idx + 1)))
+    })
 }
@@ -0,0 +1,44 @@
+use crate::{
+    ast::{Expression, Statement},
+    token::TokenType,
+};
+
+use super::{precedence::Precedence, Parser};
+
+impl Parser {
+    pub fn parse_for_statement(&mut self) -> Option<Statement> {
+        let token = self.curr_token.clone();
+
+        if !self.expect_peek(TokenType::IDENT) {
+            return None;
+        }
+        let variable = Expression::Identifier {
+            token: self.curr_token.clone(),
+            value: self.curr_token.literal.clone(),
+        };
+
+        if !self.expect_peek(TokenType::IN) {
+            return None;
+        }
+        self.next_token();
+        let iterable = self.parse_expression(Precedence::LOWEST);
+        if !self.expect_peek(TokenType::LBRACE) {
+            return None;
+        }
+
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            self.next_token();
+        }
+
+        Some(Statement::ForStatement {
+            token,
+            variable,
+            iterable,
+            body: Box::new(body?),
+        })
+    }
+}
@@ -1,6 +1,6 @@
 use crate::token::TokenType;
 
-use super::{precedence::Precedence, Parser};
+use super::{precedence::Precedence, ParseError, Parser};
 
 impl Parser {
     pub fn curr_token_is(&self, token_type: TokenType) -> bool {
@@ -16,31 +16,56 @@ impl Parser {
             self.next_token();
             return true;
         }
+        self.peek_error(token_type);
         false
     }
 
     pub fn peek_error(&mut self, token_type: TokenType) {
-        self.errors.push(format!(
-            "expected token={:#?}, got {:#?}",
-            token_type, self.peek_token
-        ));
+        self.errors
+            .push(ParseError::unexpected_token(token_type, &self.peek_token));
     }
 
-    pub fn no_prefix_parse_fn_error(&mut self, token_type: TokenType) {
-        self.errors
-            .push(format!("no prefix parse fn for {:#?} found", token_type));
+    pub fn no_prefix_parse_fn_error(&mut self) {
+        let token = &self.curr_token;
+        self.errors.push(match token.token_type {
+            TokenType::ILLEGAL => ParseError::IllegalToken {
+                literal: token.literal.clone(),
+                span: token.span,
+            },
+            token_type => ParseError::NoPrefixParseFn {
+                token_type,
+                literal: token.literal.clone(),
+                span: token.span,
+            },
+        });
     }
 
-    pub fn check_parse_errors(&self) {
-        if self.errors.len() == 0 {
+    // A statement ends at a `;`, which is consumed, or before a `}`, the end
+    // of input or a token on a later line. Anything else is reported rather
+    // than skipped, so `let x = 1 2;` is an error.
+    pub fn expect_statement_end(&mut self) {
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            self.next_token();
             return;
         }
-
-        for err in self.errors.iter() {
-            println!("{}", err);
+        if self.peek_token_is(TokenType::RBRACE)
+            || self.peek_token_is(TokenType::EOF)
+            || self.peek_token.span.line > self.curr_token.span.line
+        {
+            return;
         }
+        self.errors.push(ParseError::ExpectedStatementEnd {
+            got: self.peek_token.token_type,
+            literal: self.peek_token.literal.clone(),
+            span: self.peek_token.span,
+        });
+    }
 
-        panic!("error reported in parsing!!!");
+    // Skips the rest of a broken statement so one mistake is reported once.
+    pub fn synchronize(&mut self) {
+        while !self.curr_token_is(TokenType::SEMICOLON) && !self.curr_token_is(TokenType::EOF) {
+            self.next_token();
+        }
     }
 
     pub fn peek_precedence(&self) -> Precedence {
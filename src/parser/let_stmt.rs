@@ -8,9 +8,9 @@ use super::Parser;
 impl Parser {
     pub fn parse_let_statement(&mut self) -> Option<Statement> {
         let mut stmt = Statement::new(TokenType::LET);
+        stmt.set_token(self.curr_token.clone());
 
         if !self.expect_peek(TokenType::IDENT) {
-            self.peek_error(TokenType::IDENT);
             return None;
         }
 
@@ -20,17 +20,16 @@ impl Parser {
         });
 
         if !self.expect_peek(TokenType::ASSIGN) {
-            self.peek_error(TokenType::ASSIGN);
             return None;
         }
 
         self.next_token();
+        let errors = self.errors.len();
         stmt.set_value(self.parse_expression(super::precedence::Precedence::LOWEST));
-
-        while !self.curr_token_is(TokenType::SEMICOLON) && !self.curr_token_is(TokenType::EOF) {
-            self.next_token();
+        if self.errors.len() == errors {
+            self.expect_statement_end();
         }
 
-        return Some(stmt);
+        Some(stmt)
     }
 }
@@ -1,9 +1,14 @@
 mod block_stmt;
 mod expr_stmt;
+mod for_stmt;
 mod helper;
 mod let_stmt;
+mod parse_error;
 mod precedence;
 mod return_stmt;
+mod while_stmt;
+
+pub use parse_error::ParseError;
 
 use std::collections::HashMap;
 
@@ -19,7 +24,9 @@ pub struct Parser {
     l: Lexer,
     curr_token: Token,
     peek_token: Token,
-    pub errors: Vec<String>,
+    errors: Vec<ParseError>,
+    // How many loops enclose the current statement within its function.
+    loop_depth: usize,
 
     prefix_parse_fns: HashMap<TokenType, for<'a> fn(&'a mut Parser) -> Expression>,
     infix_parse_fns: HashMap<TokenType, for<'a> fn(&'a mut Parser, &Expression) -> Expression>,
@@ -29,40 +36,47 @@ impl Parser {
     pub fn new(l: Lexer) -> Parser {
         let mut p = Parser {
             l,
-            curr_token: Token {
-                token_type: TokenType::ILLEGAL,
-                literal: sf!("\0"),
-            },
-            peek_token: Token {
-                token_type: TokenType::ILLEGAL,
-                literal: sf!("\0"),
-            },
+            curr_token: Token::new(TokenType::ILLEGAL, sf!("\0")),
+            peek_token: Token::new(TokenType::ILLEGAL, sf!("\0")),
             errors: vec![],
+            loop_depth: 0,
             prefix_parse_fns: HashMap::new(),
             infix_parse_fns: HashMap::new(),
         };
 
         p.register_prefix(TokenType::IDENT, Parser::parse_identifier);
         p.register_prefix(TokenType::INT, Parser::parse_integer_literal);
+        p.register_prefix(TokenType::FLOAT, Parser::parse_float_literal);
         p.register_prefix(TokenType::BANG, Parser::parse_prefix_expression);
         p.register_prefix(TokenType::MINUS, Parser::parse_prefix_expression);
+        p.register_prefix(TokenType::STRING, Parser::parse_string_literal);
         p.register_prefix(TokenType::TRUE, Parser::parse_boolean);
         p.register_prefix(TokenType::FALSE, Parser::parse_boolean);
         p.register_prefix(TokenType::LPAREN, Parser::parse_grouped_expression);
-        p.register_prefix(TokenType::RPAREN, Parser::parse_grouped_expression);
-        p.register_prefix(TokenType::PLUS, Parser::parse_grouped_expression);
         p.register_prefix(TokenType::IF, Parser::parse_if_expression);
         p.register_prefix(TokenType::FUNCTION, Parser::parse_func_expression);
+        p.register_prefix(TokenType::LBRACKET, Parser::parse_array_literal);
+        p.register_prefix(TokenType::LBRACE, Parser::parse_hash_literal);
 
         p.register_infix(TokenType::PLUS, Parser::parse_infix_expression);
         p.register_infix(TokenType::MINUS, Parser::parse_infix_expression);
         p.register_infix(TokenType::SLASH, Parser::parse_infix_expression);
         p.register_infix(TokenType::ASTERISK, Parser::parse_infix_expression);
+        p.register_infix(TokenType::PERCENT, Parser::parse_infix_expression);
         p.register_infix(TokenType::EQ, Parser::parse_infix_expression);
         p.register_infix(TokenType::NOTEQ, Parser::parse_infix_expression);
         p.register_infix(TokenType::LT, Parser::parse_infix_expression);
         p.register_infix(TokenType::GT, Parser::parse_infix_expression);
+        p.register_infix(TokenType::LTEQ, Parser::parse_infix_expression);
+        p.register_infix(TokenType::GTEQ, Parser::parse_infix_expression);
+        p.register_infix(TokenType::DOTDOT, Parser::parse_infix_expression);
+        p.register_infix(TokenType::ASSIGN, Parser::parse_assign_expression);
+        p.register_infix(TokenType::PLUSASSIGN, Parser::parse_assign_expression);
+        p.register_infix(TokenType::MINUSASSIGN, Parser::parse_assign_expression);
+        p.register_infix(TokenType::ASTERISKASSIGN, Parser::parse_assign_expression);
+        p.register_infix(TokenType::SLASHASSIGN, Parser::parse_assign_expression);
         p.register_infix(TokenType::LPAREN, Parser::parse_call_expression);
+        p.register_infix(TokenType::LBRACKET, Parser::parse_index_expression);
 
         p.next_token();
         p.next_token();
@@ -72,29 +86,44 @@ impl Parser {
     fn next_token(&mut self) {
         self.curr_token = self.peek_token.clone();
         self.peek_token = self.l.next_token();
+
+        // An unterminated comment swallows the rest of the input, so it is
+        // reported here rather than wherever the parser happens to be.
+        self.errors.extend(self.l.take_errors());
     }
 
-    pub fn parse_program(&mut self) -> Program<Statement> {
+    pub fn parse_program(&mut self) -> Result<Program<Statement>, Vec<ParseError>> {
         let mut program = Program { statements: vec![] };
 
         while self.curr_token.token_type != TokenType::EOF {
+            let errors = self.errors.len();
             let stmt = self.parse_statement();
 
-            match stmt {
-                Some(st) => program.statements.push(st),
-                None => {}
-            };
+            if self.errors.len() > errors {
+                self.synchronize();
+            }
+
+            if let Some(st) = stmt {
+                program.statements.push(st);
+            }
 
             self.next_token();
         }
 
-        program
+        if !self.errors.is_empty() {
+            return Err(self.errors.clone());
+        }
+
+        Ok(program)
     }
 
     fn parse_statement(&mut self) -> Option<Statement> {
         match self.curr_token.token_type {
             TokenType::LET => self.parse_let_statement(),
             TokenType::RETURN => self.parse_return_statement(),
+            TokenType::WHILE => self.parse_while_statement(),
+            TokenType::FOR => self.parse_for_statement(),
+            TokenType::BREAK | TokenType::CONTINUE => self.parse_loop_control_statement(),
             _ => self.parse_expression_statement(),
         }
     }
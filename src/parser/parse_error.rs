@@ -0,0 +1,169 @@
+use std::fmt;
+
+use crate::token::{Span, Token, TokenType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken {
+        expected: TokenType,
+        got: TokenType,
+        literal: String,
+        span: Span,
+    },
+    NoPrefixParseFn {
+        token_type: TokenType,
+        literal: String,
+        span: Span,
+    },
+    UnterminatedBlock {
+        span: Span,
+    },
+    UnterminatedComment {
+        span: Span,
+    },
+    InvalidInteger {
+        literal: String,
+        span: Span,
+    },
+    NumberOutOfRange {
+        literal: String,
+        span: Span,
+    },
+    InvalidDigit {
+        digit: char,
+        radix: u32,
+        span: Span,
+    },
+    MisplacedSeparator {
+        span: Span,
+    },
+    IllegalToken {
+        literal: String,
+        span: Span,
+    },
+    ExpectedStatementEnd {
+        got: TokenType,
+        literal: String,
+        span: Span,
+    },
+    RequiredAfterDefault {
+        name: String,
+        span: Span,
+    },
+    OutsideLoop {
+        keyword: String,
+        span: Span,
+    },
+    InvalidAssignmentTarget {
+        target: String,
+        span: Span,
+    },
+}
+
+impl ParseError {
+    pub fn unexpected_token(expected: TokenType, got: &Token) -> ParseError {
+        ParseError::UnexpectedToken {
+            expected,
+            got: got.token_type,
+            literal: got.literal.clone(),
+            span: got.span,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. } => *span,
+            ParseError::NoPrefixParseFn { span, .. } => *span,
+            ParseError::UnterminatedBlock { span } => *span,
+            ParseError::UnterminatedComment { span } => *span,
+            ParseError::InvalidInteger { span, .. } => *span,
+            ParseError::NumberOutOfRange { span, .. } => *span,
+            ParseError::InvalidDigit { span, .. } => *span,
+            ParseError::MisplacedSeparator { span } => *span,
+            ParseError::IllegalToken { span, .. } => *span,
+            ParseError::ExpectedStatementEnd { span, .. } => *span,
+            ParseError::RequiredAfterDefault { span, .. } => *span,
+            ParseError::OutsideLoop { span, .. } => *span,
+            ParseError::InvalidAssignmentTarget { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedToken {
+                expected,
+                got: TokenType::EOF,
+                ..
+            } => write!(f, "expected token {:?}, got end of input", expected),
+            ParseError::UnexpectedToken {
+                expected,
+                got,
+                literal,
+                ..
+            } => write!(
+                f,
+                "expected token {:?}, got {:?} `{}`",
+                expected,
+                got,
+                literal.escape_default()
+            ),
+            ParseError::NoPrefixParseFn {
+                token_type: TokenType::EOF,
+                ..
+            } => write!(f, "unexpected end of input"),
+            ParseError::NoPrefixParseFn {
+                token_type,
+                literal,
+                ..
+            } => write!(
+                f,
+                "no prefix parse fn for {:?} `{}` found",
+                token_type,
+                literal.escape_default()
+            ),
+            ParseError::UnterminatedBlock { .. } => {
+                write!(f, "unterminated block, expected RBRACE before end of input")
+            }
+            ParseError::UnterminatedComment { .. } => {
+                write!(
+                    f,
+                    "unterminated block comment, expected `*/` before end of input"
+                )
+            }
+            ParseError::InvalidInteger { literal, .. } => {
+                write!(f, "could not parse `{}` as integer", literal)
+            }
+            ParseError::NumberOutOfRange { literal, .. } => {
+                write!(f, "number `{}` is too large to represent", literal)
+            }
+            ParseError::InvalidDigit { digit, radix, .. } => {
+                write!(f, "invalid digit `{}` in a base {} literal", digit, radix)
+            }
+            ParseError::MisplacedSeparator { .. } => {
+                write!(f, "misplaced `_`, separators go between two digits")
+            }
+            ParseError::IllegalToken { literal, .. } => {
+                write!(f, "illegal token `{}`", literal.escape_default())
+            }
+            ParseError::ExpectedStatementEnd { got, literal, .. } => write!(
+                f,
+                "expected `;` or end of statement, got {:?} `{}`",
+                got,
+                literal.escape_default()
+            ),
+            ParseError::RequiredAfterDefault { name, .. } => write!(
+                f,
+                "parameter `{}` without a default follows a parameter with one",
+                name
+            ),
+            ParseError::OutsideLoop { keyword, .. } => {
+                write!(f, "`{}` outside of a loop", keyword)
+            }
+            ParseError::InvalidAssignmentTarget { target, .. } => {
+                write!(f, "cannot assign to `{}`", target)
+            }
+        }
+    }
+}
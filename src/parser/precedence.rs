@@ -1,29 +1,43 @@
 use crate::token::TokenType;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(PartialEq, PartialOrd)]
 pub enum Precedence {
     _BLANK,
     LOWEST,
+    ASSIGN,
+    RANGE,
     EQUALS,
     LESSGREATER,
     SUM,
     PRODUCT,
     PREFIX,
     CALL,
+    INDEX,
 }
 
 impl Precedence {
     pub fn lookup_precedence(token_type: TokenType) -> Precedence {
         match token_type {
+            TokenType::ASSIGN
+            | TokenType::PLUSASSIGN
+            | TokenType::MINUSASSIGN
+            | TokenType::ASTERISKASSIGN
+            | TokenType::SLASHASSIGN => Precedence::ASSIGN,
+            TokenType::DOTDOT => Precedence::RANGE,
             TokenType::EQ => Precedence::EQUALS,
             TokenType::NOTEQ => Precedence::EQUALS,
             TokenType::LT => Precedence::LESSGREATER,
             TokenType::GT => Precedence::LESSGREATER,
+            TokenType::LTEQ => Precedence::LESSGREATER,
+            TokenType::GTEQ => Precedence::LESSGREATER,
             TokenType::PLUS => Precedence::SUM,
             TokenType::MINUS => Precedence::SUM,
             TokenType::SLASH => Precedence::PRODUCT,
             TokenType::ASTERISK => Precedence::PRODUCT,
+            TokenType::PERCENT => Precedence::PRODUCT,
             TokenType::LPAREN => Precedence::CALL,
+            TokenType::LBRACKET => Precedence::INDEX,
             _ => Precedence::LOWEST,
         }
     }
@@ -5,14 +5,15 @@ use super::Parser;
 impl Parser {
     pub fn parse_return_statement(&mut self) -> Option<Statement> {
         let mut stmt = Statement::new(TokenType::RETURN);
+        stmt.set_token(self.curr_token.clone());
 
         self.next_token();
+        let errors = self.errors.len();
         stmt.set_value(self.parse_expression(super::precedence::Precedence::LOWEST));
-
-        while !self.curr_token_is(TokenType::SEMICOLON) && !self.curr_token_is(TokenType::EOF) {
-            self.next_token();
+        if self.errors.len() == errors {
+            self.expect_statement_end();
         }
 
-        return Some(stmt);
+        Some(stmt)
     }
 }
@@ -0,0 +1,53 @@
+use crate::{ast::Statement, token::TokenType};
+
+use super::{precedence::Precedence, ParseError, Parser};
+
+impl Parser {
+    // Like `if`, the condition needs no parentheses.
+    pub fn parse_while_statement(&mut self) -> Option<Statement> {
+        let token = self.curr_token.clone();
+
+        // The condition is part of the loop, so `break` and `continue` may
+        // appear in it too.
+        self.loop_depth += 1;
+        self.next_token();
+        let condition = self.parse_expression(Precedence::LOWEST);
+        let body = if self.expect_peek(TokenType::LBRACE) {
+            self.parse_block_statement()
+        } else {
+            None
+        };
+        self.loop_depth -= 1;
+
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            self.next_token();
+        }
+
+        Some(Statement::WhileStatement {
+            token,
+            condition,
+            body: Box::new(body?),
+        })
+    }
+
+    // `break` and `continue` only make sense inside a loop of the same
+    // function, so anything else is rejected here rather than at run time.
+    pub fn parse_loop_control_statement(&mut self) -> Option<Statement> {
+        let token = self.curr_token.clone();
+
+        if self.loop_depth == 0 {
+            self.errors.push(ParseError::OutsideLoop {
+                keyword: token.literal.clone(),
+                span: token.span,
+            });
+        }
+        if self.peek_token_is(TokenType::SEMICOLON) {
+            self.next_token();
+        }
+
+        Some(match token.token_type {
+            TokenType::BREAK => Statement::BreakStatement { token },
+            _ => Statement::ContinueStatement { token },
+        })
+    }
+}
@@ -0,0 +1,100 @@
+use std::{cell::RefCell, fs, rc::Rc};
+
+use macros::sf;
+
+use crate::environment::Environment;
+
+use super::{lpe, print_ast, print_tokens, Backend, STDIN_NAME};
+
+const HELP: &str = ":help            show this help
+:env             list the session bindings and their types
+:type <expr>     evaluate <expr> and print the type of its value
+:ast <expr>      print the parsed syntax tree of <expr>
+:tokens <expr>   print the tokens of <expr>
+:load <file>     run <file> in the current session
+:reset           forget all bindings
+:quit            leave the prompt (as does Ctrl-D)";
+
+/// A session command typed at the prompt, such as `:env` or `:type 1 + 2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Env,
+    Type(String),
+    Ast(String),
+    Tokens(String),
+    Load(String),
+    Reset,
+    Quit,
+}
+
+impl Command {
+    /// Parses a line starting with `:`. The error is meant for the user.
+    pub fn parse(line: &str) -> Result<Command, String> {
+        let line = line.trim();
+        let (name, arg) = match line.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (line, ""),
+        };
+
+        let needs_arg = |command: fn(String) -> Command, what: &str| {
+            if arg.is_empty() {
+                Err(format!("`{}` needs {}", name, what))
+            } else {
+                Ok(command(sf!(arg)))
+            }
+        };
+        let no_arg = |command: Command| {
+            if arg.is_empty() {
+                Ok(command)
+            } else {
+                Err(format!("`{}` takes no argument", name))
+            }
+        };
+
+        match name {
+            ":help" => no_arg(Command::Help),
+            ":env" => no_arg(Command::Env),
+            ":type" => needs_arg(Command::Type, "an expression"),
+            ":ast" => needs_arg(Command::Ast, "an expression"),
+            ":tokens" => needs_arg(Command::Tokens, "an expression"),
+            ":load" => needs_arg(Command::Load, "a file"),
+            ":reset" => no_arg(Command::Reset),
+            ":quit" => no_arg(Command::Quit),
+            _ => Err(format!("unknown command `{}`, see :help", name)),
+        }
+    }
+
+    /// Runs the command against the session. `Quit` is left to the caller.
+    pub fn run(self, env: &mut Rc<RefCell<Environment>>, backend: Backend) {
+        match self {
+            Command::Help => println!("{}", HELP),
+            Command::Env => env
+                .borrow()
+                .bindings()
+                .iter()
+                .for_each(|(name, value)| println!("{}: {}", name, value.get_type())),
+            // Runs against a copy of the session scope, so `let`s and
+            // assignments stay out of it. Functions still see the scope they
+            // were defined in.
+            Command::Type(input) => {
+                let scope = Rc::new(RefCell::new(env.borrow().clone()));
+                if let Ok(value) = lpe(input, STDIN_NAME, &scope, backend) {
+                    println!("{}", value.get_type());
+                }
+            }
+            Command::Ast(input) => {
+                let _ = print_ast(&input, STDIN_NAME);
+            }
+            Command::Tokens(input) => print_tokens(&input, STDIN_NAME),
+            Command::Load(filename) => match fs::read_to_string(&filename) {
+                Ok(input) => {
+                    let _ = lpe(input, &filename, env, backend);
+                }
+                Err(err) => eprintln!("error: cannot read `{}`: {}", filename, err),
+            },
+            Command::Reset => *env = Rc::new(RefCell::new(Environment::new())),
+            Command::Quit => {}
+        }
+    }
+}
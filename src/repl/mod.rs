@@ -0,0 +1,254 @@
+mod commands;
+
+use std::{cell::RefCell, env, path::PathBuf, rc::Rc};
+
+use rustyline::{error::ReadlineError, DefaultEditor};
+
+use crate::{
+    ast::{Node, Program, Statement},
+    compiler,
+    diagnostics::Diagnostic,
+    environment::Environment,
+    evaluator::eval_statements,
+    interpreter::DLangError,
+    lexer::Lexer,
+    object::Object,
+    parser::Parser,
+    token::TokenType,
+    vm,
+};
+
+pub use commands::Command;
+
+const PROMT: &str = ">>> ";
+const CONTINUATION_PROMT: &str = "... ";
+const HISTORY_FILE: &str = ".d_lang_history";
+const STDIN_NAME: &str = "<stdin>";
+
+/// Which engine runs parsed programs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    #[default]
+    Evaluator,
+    Vm,
+}
+
+/// Parses and runs `input`, printing diagnostics for any errors to stderr.
+/// Nothing else is printed; scripts write output with `puts` and `print`.
+pub fn lpe(
+    input: String,
+    filename: &str,
+    env: &Rc<RefCell<Environment>>,
+    backend: Backend,
+) -> Result<Object, DLangError> {
+    let program = parse(&input, filename)?;
+    run_program(program, &input, filename, env, backend)
+}
+
+/// Runs a parsed `program`, printing diagnostics for any errors in `input`
+/// to stderr.
+fn run_program(
+    program: Program<Statement>,
+    input: &str,
+    filename: &str,
+    env: &Rc<RefCell<Environment>>,
+    backend: Backend,
+) -> Result<Object, DLangError> {
+    let result = match backend {
+        Backend::Evaluator => eval_statements(program.statements, env),
+        Backend::Vm => {
+            let function = compiler::compile(&program.statements).map_err(|err| {
+                eprint!("{}", Diagnostic::from(&err).render(input, filename));
+                DLangError::Compile(err)
+            })?;
+            vm::run(Rc::new(function), env)
+        }
+    };
+
+    result.map_err(|err| {
+        eprint!("{}", Diagnostic::from(&err).render(input, filename));
+        DLangError::Runtime(err)
+    })
+}
+
+/// Parses `input`, printing diagnostics for any errors to stderr.
+pub(crate) fn parse(input: &str, filename: &str) -> Result<Program<Statement>, DLangError> {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+
+    p.parse_program().map_err(|errors| {
+        for err in errors.iter() {
+            eprint!("{}", Diagnostic::from(err).render(input, filename));
+        }
+        DLangError::Parse(errors)
+    })
+}
+
+/// Prints every token of `input` with its position, up to and including EOF,
+/// and diagnostics for anything the lexer rejected to stderr.
+pub(crate) fn print_tokens(input: &str, filename: &str) {
+    let mut l = Lexer::new(input.chars().collect());
+    loop {
+        let token = l.next_token();
+        println!(
+            "{}:{} {:?} {:?}",
+            token.span.line, token.span.column, token.token_type, token.literal
+        );
+        if token.token_type == TokenType::EOF {
+            break;
+        }
+    }
+    for err in l.take_errors().iter() {
+        eprint!("{}", Diagnostic::from(err).render(input, filename));
+    }
+}
+
+/// Prints the statements of `input` as source text.
+pub(crate) fn print_ast(input: &str, filename: &str) -> Result<(), DLangError> {
+    parse(input, filename)?
+        .statements
+        .iter()
+        .for_each(|stmt| println!("{}", stmt.to_string()));
+    Ok(())
+}
+
+/// Runs the interactive prompt until EOF. Lines starting with `:` are
+/// session commands, see `:help`. Input that is still open (an
+/// unclosed bracket or a trailing operator) continues on the next line, and
+/// entries are kept in a history file in the home directory.
+pub fn run(backend: Backend) {
+    let mut env = Rc::new(RefCell::new(Environment::new()));
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("error: cannot start the prompt: {}", err);
+            return;
+        }
+    };
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means a first run.
+        let _ = editor.load_history(path);
+    }
+
+    let mut input = String::new();
+    loop {
+        let prompt = if input.is_empty() {
+            PROMT
+        } else {
+            CONTINUATION_PROMT
+        };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                input.push_str(&line);
+                input.push('\n');
+            }
+            // Ctrl-C drops whatever has been typed so far.
+            Err(ReadlineError::Interrupted) => {
+                input.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                break;
+            }
+        }
+
+        if input.trim_start().starts_with(':') {
+            let line = std::mem::take(&mut input);
+            let _ = editor.add_history_entry(line.trim());
+            match Command::parse(&line) {
+                Ok(Command::Quit) => break,
+                Ok(command) => command.run(&mut env, backend),
+                Err(message) => eprintln!("error: {}", message),
+            }
+            continue;
+        }
+        if is_incomplete(&input) {
+            continue;
+        }
+        let entry = std::mem::take(&mut input);
+        if entry.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(entry.trim_end());
+
+        // Only the prompt echoes results. Entries ending in a `let` and
+        // results of `null`, such as calls to `puts`, stay quiet.
+        let Ok(program) = parse(&entry, STDIN_NAME) else {
+            continue;
+        };
+        let quiet = matches!(
+            program.statements.last(),
+            Some(Statement::LetStatement { .. })
+        );
+        match run_program(program, &entry, STDIN_NAME, &env, backend) {
+            Ok(Object::Null {}) | Err(_) => {}
+            Ok(_) if quiet => {}
+            Ok(result) => println!("{}", result.inspect()),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(err) = editor.save_history(path) {
+            eprintln!("error: cannot save history to {}: {}", path.display(), err);
+        }
+    }
+}
+
+/// Whether `input` needs more lines before it can be parsed: a bracket,
+/// string or block comment is still open, or the last token is an operator
+/// waiting for its right-hand side.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut l = Lexer::new(input.chars().collect());
+    let mut depth: i32 = 0;
+    let mut last = None;
+    loop {
+        let token = l.next_token();
+        match token.token_type {
+            TokenType::EOF => break,
+            TokenType::LPAREN | TokenType::LBRACE | TokenType::LBRACKET => depth += 1,
+            TokenType::RPAREN | TokenType::RBRACE | TokenType::RBRACKET => depth -= 1,
+            _ => {}
+        }
+        last = Some(token);
+    }
+
+    // The only lexer error is a block comment running to the end.
+    let open_comment = !l.take_errors().is_empty();
+    let Some(last) = last else {
+        return open_comment;
+    };
+    open_comment
+        || depth > 0
+        || matches!(
+            last.token_type,
+            TokenType::ASSIGN
+                | TokenType::PLUSASSIGN
+                | TokenType::MINUSASSIGN
+                | TokenType::ASTERISKASSIGN
+                | TokenType::SLASHASSIGN
+                | TokenType::PLUS
+                | TokenType::MINUS
+                | TokenType::BANG
+                | TokenType::ASTERISK
+                | TokenType::PERCENT
+                | TokenType::SLASH
+                | TokenType::LT
+                | TokenType::GT
+                | TokenType::LTEQ
+                | TokenType::GTEQ
+                | TokenType::EQ
+                | TokenType::NOTEQ
+                | TokenType::COMMA
+                | TokenType::COLON
+                | TokenType::DOTDOT
+                | TokenType::IN
+        )
+        || (last.token_type == TokenType::ILLEGAL && last.literal == "\"")
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::home_dir().map(|home| home.join(HISTORY_FILE))
+}
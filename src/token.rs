@@ -1,3 +1,4 @@
+#[allow(clippy::upper_case_acronyms)]
 #[derive(PartialEq, Debug, Clone, Copy, Eq, Hash)]
 pub enum TokenType {
     ILLEGAL,
@@ -7,19 +8,28 @@ pub enum TokenType {
     // Identifiers + literals;
     IDENT,
     INT,
+    FLOAT,
     STRING,
 
     // Operators;
     ASSIGN,
+    PLUSASSIGN,
+    MINUSASSIGN,
+    ASTERISKASSIGN,
+    SLASHASSIGN,
     PLUS,
     MINUS,
     BANG,
     ASTERISK,
+    PERCENT,
     SLASH,
+    DOTDOT,
 
     // Comparision
     LT,
     GT,
+    LTEQ,
+    GTEQ,
     EQ,
     NOTEQ,
 
@@ -44,15 +54,107 @@ pub enum TokenType {
     IF,
     ELSE,
     RETURN,
+    WHILE,
+    BREAK,
+    CONTINUE,
+    FOR,
+    IN,
+}
+
+impl TokenType {
+    // Inverse of the `Debug` name, used when reading serialized tokens.
+    pub fn from_name(name: &str) -> Option<TokenType> {
+        let token_type = match name {
+            "ILLEGAL" => TokenType::ILLEGAL,
+            "EOF" => TokenType::EOF,
+            "BLOCK" => TokenType::BLOCK,
+            "IDENT" => TokenType::IDENT,
+            "INT" => TokenType::INT,
+            "FLOAT" => TokenType::FLOAT,
+            "STRING" => TokenType::STRING,
+            "ASSIGN" => TokenType::ASSIGN,
+            "PLUSASSIGN" => TokenType::PLUSASSIGN,
+            "MINUSASSIGN" => TokenType::MINUSASSIGN,
+            "ASTERISKASSIGN" => TokenType::ASTERISKASSIGN,
+            "SLASHASSIGN" => TokenType::SLASHASSIGN,
+            "PLUS" => TokenType::PLUS,
+            "MINUS" => TokenType::MINUS,
+            "BANG" => TokenType::BANG,
+            "ASTERISK" => TokenType::ASTERISK,
+            "PERCENT" => TokenType::PERCENT,
+            "SLASH" => TokenType::SLASH,
+            "DOTDOT" => TokenType::DOTDOT,
+            "LT" => TokenType::LT,
+            "GT" => TokenType::GT,
+            "LTEQ" => TokenType::LTEQ,
+            "GTEQ" => TokenType::GTEQ,
+            "EQ" => TokenType::EQ,
+            "NOTEQ" => TokenType::NOTEQ,
+            "COMMA" => TokenType::COMMA,
+            "SEMICOLON" => TokenType::SEMICOLON,
+            "COLON" => TokenType::COLON,
+            "LPAREN" => TokenType::LPAREN,
+            "RPAREN" => TokenType::RPAREN,
+            "LBRACE" => TokenType::LBRACE,
+            "RBRACE" => TokenType::RBRACE,
+            "LBRACKET" => TokenType::LBRACKET,
+            "RBRACKET" => TokenType::RBRACKET,
+            "FUNCTION" => TokenType::FUNCTION,
+            "LET" => TokenType::LET,
+            "TRUE" => TokenType::TRUE,
+            "FALSE" => TokenType::FALSE,
+            "IF" => TokenType::IF,
+            "ELSE" => TokenType::ELSE,
+            "RETURN" => TokenType::RETURN,
+            "WHILE" => TokenType::WHILE,
+            "BREAK" => TokenType::BREAK,
+            "CONTINUE" => TokenType::CONTINUE,
+            "FOR" => TokenType::FOR,
+            "IN" => TokenType::IN,
+            _ => return None,
+        };
+        Some(token_type)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Span {
+    // Stretches a span to the end of `other`; spans over several lines keep
+    // only their first line.
+    pub fn to(self, other: Span) -> Span {
+        if self.line != other.line || other.column < self.column {
+            return self;
+        }
+        Span {
+            line: self.line,
+            column: self.column,
+            len: other.column + other.len - self.column,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: String,
+    pub span: Span,
 }
 
 impl Token {
+    pub fn new(token_type: TokenType, literal: String) -> Token {
+        Token {
+            token_type,
+            literal,
+            span: Span::default(),
+        }
+    }
+
     pub fn lookup_ident(ident: &str) -> TokenType {
         match ident {
             "fn" => TokenType::FUNCTION,
@@ -62,6 +164,11 @@ impl Token {
             "if" => TokenType::IF,
             "else" => TokenType::ELSE,
             "return" => TokenType::RETURN,
+            "while" => TokenType::WHILE,
+            "break" => TokenType::BREAK,
+            "continue" => TokenType::CONTINUE,
+            "for" => TokenType::FOR,
+            "in" => TokenType::IN,
             _ => TokenType::IDENT,
         }
     }
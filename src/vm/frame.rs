@@ -0,0 +1,39 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{compiler::CompiledFunction, environment::Environment, object::Object};
+
+pub struct Frame {
+    pub function: Rc<CompiledFunction>,
+    pub ip: usize,
+    pub env: Rc<RefCell<Environment>>,
+    // Stack height when the frame was entered; returning truncates to it.
+    pub base_pointer: usize,
+    pub argc: usize,
+    // Iterators of the `for` loops running in this frame, innermost last.
+    pub iterators: Vec<Box<dyn Iterator<Item = Object>>>,
+    // Scopes that the loop scopes in `env` were pushed over.
+    pub scopes: Vec<Rc<RefCell<Environment>>>,
+    // Stack heights at the start of the loops running in this frame, which
+    // `break` and `continue` truncate back to.
+    pub loops: Vec<usize>,
+}
+
+impl Frame {
+    pub fn new(
+        function: Rc<CompiledFunction>,
+        env: Rc<RefCell<Environment>>,
+        base_pointer: usize,
+        argc: usize,
+    ) -> Frame {
+        Frame {
+            function,
+            ip: 0,
+            env,
+            base_pointer,
+            argc,
+            iterators: vec![],
+            scopes: vec![],
+            loops: vec![],
+        }
+    }
+}
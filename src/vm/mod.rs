@@ -0,0 +1,295 @@
+mod frame;
+
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use macros::sf;
+
+use crate::{
+    builtins,
+    compiler::{
+        code::{read_operand, Opcode},
+        CompiledFunction,
+    },
+    environment::Environment,
+    evaluator::{self, RuntimeError},
+    object::Object,
+    token::Span,
+};
+
+use frame::Frame;
+
+/// Runs a compiled program against `env` and returns the value of its last
+/// statement. Bindings are resolved by name through the environment chain,
+/// so closures see the same scopes they would under the evaluator.
+pub fn run(
+    function: Rc<CompiledFunction>,
+    env: &Rc<RefCell<Environment>>,
+) -> Result<Object, RuntimeError> {
+    let mut vm = Vm {
+        stack: vec![],
+        frames: vec![Frame::new(function, Rc::clone(env), 0, 0)],
+    };
+    vm.execute()
+}
+
+struct Vm {
+    stack: Vec<Object>,
+    frames: Vec<Frame>,
+}
+
+impl Vm {
+    fn execute(&mut self) -> Result<Object, RuntimeError> {
+        loop {
+            let frame = self.frames.last_mut().expect("vm has no frame");
+            let ins = &frame.function.instructions;
+            let pos = frame.ip;
+            let op = Opcode::from_byte(ins[pos]).expect("invalid opcode");
+
+            let mut operands = [0; 2];
+            let mut offset = pos + 1;
+            for (i, width) in op.operand_widths().iter().enumerate() {
+                operands[i] = read_operand(*width, &ins[offset..]);
+                offset += width;
+            }
+            frame.ip = offset;
+
+            match self.execute_op(op, operands) {
+                Ok(Some(result)) => return Ok(result),
+                Ok(None) => {}
+                Err(err) => {
+                    // Errors leave the faulting frame on top.
+                    let frame = self.frames.last().expect("vm has no frame");
+                    return Err(err.with_span(frame.function.span_at(pos)));
+                }
+            }
+        }
+    }
+
+    // Returns the program result once the top-level frame returns.
+    fn execute_op(
+        &mut self,
+        op: Opcode,
+        operands: [usize; 2],
+    ) -> Result<Option<Object>, RuntimeError> {
+        match op {
+            Opcode::Constant => {
+                let constant = self.frame().function.constants[operands[0]].clone();
+                self.stack.push(constant);
+            }
+            Opcode::Pop => {
+                self.pop();
+            }
+            Opcode::True => self.stack.push(Object::Boolean { value: true }),
+            Opcode::False => self.stack.push(Object::Boolean { value: false }),
+            Opcode::Null => self.stack.push(Object::Null {}),
+            Opcode::Add
+            | Opcode::Sub
+            | Opcode::Mul
+            | Opcode::Div
+            | Opcode::Mod
+            | Opcode::Equal
+            | Opcode::NotEqual
+            | Opcode::LessThan
+            | Opcode::GreaterThan
+            | Opcode::LessEqual
+            | Opcode::GreaterEqual
+            | Opcode::Range => {
+                let right = self.pop();
+                let left = self.pop();
+                let result = evaluator::eval_infix_expr(left, sf!(infix_operator(op)), right)?;
+                self.stack.push(result);
+            }
+            Opcode::Minus | Opcode::Bang => {
+                let right = self.pop();
+                let operator = if op == Opcode::Minus { "-" } else { "!" };
+                let result = evaluator::eval_prefix_expr(sf!(operator), right)?;
+                self.stack.push(result);
+            }
+            Opcode::Jump => self.frame_mut().ip = operands[0],
+            Opcode::JumpNotTruthy => {
+                let condition = self.pop();
+                if !evaluator::is_truthy(&condition) {
+                    self.frame_mut().ip = operands[0];
+                }
+            }
+            Opcode::GetName => {
+                let name = self.frame().function.names[operands[0]].clone();
+                let value = self.frame().env.borrow().get(name.clone());
+                match value.or_else(|| builtins::lookup(&name)) {
+                    Some(value) => self.stack.push(value),
+                    None => {
+                        return Err(RuntimeError::UnknownIdentifier {
+                            name,
+                            span: Span::default(),
+                        })
+                    }
+                }
+            }
+            Opcode::SetName => {
+                let name = self.frame().function.names[operands[0]].clone();
+                let value = self.stack.last().expect("stack underflow").clone();
+                evaluator::assign(&self.frame().env, name, value)?;
+            }
+            Opcode::Define => {
+                let name = self.frame().function.names[operands[0]].clone();
+                let value = self.stack.last().expect("stack underflow").clone();
+                self.frame().env.borrow_mut().set(name, value);
+            }
+            Opcode::Array => {
+                let elements = self.stack.split_off(self.stack.len() - operands[0]);
+                self.stack.push(Object::Array { elements });
+            }
+            Opcode::Hash => {
+                let items = self.stack.split_off(self.stack.len() - 2 * operands[0]);
+                let mut pairs = BTreeMap::new();
+                let mut items = items.into_iter();
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    pairs.insert(evaluator::hash_key(&key)?, (key, value));
+                }
+                self.stack.push(Object::Hash { pairs });
+            }
+            Opcode::Index => {
+                let index = self.pop();
+                let left = self.pop();
+                let result = evaluator::eval_index_expr(left, index)?;
+                self.stack.push(result);
+            }
+            Opcode::Call => self.call_function(operands[0])?,
+            Opcode::ReturnValue => {
+                let value = self.pop();
+                let frame = self.frames.pop().expect("vm has no frame");
+                if self.frames.is_empty() {
+                    return Ok(Some(value));
+                }
+                self.stack.truncate(frame.base_pointer);
+                self.stack.push(value);
+            }
+            Opcode::Closure => {
+                let function = Rc::clone(&self.frame().function.functions[operands[0]]);
+                let env = Rc::clone(&self.frame().env);
+                self.stack.push(Object::Closure { function, env });
+            }
+            Opcode::Default => {
+                if self.frame().argc > operands[0] {
+                    self.frame_mut().ip = operands[1];
+                }
+            }
+            Opcode::Iter => {
+                let iterable = self.pop();
+                let iterator = evaluator::iterate(iterable)?;
+                self.frame_mut().iterators.push(iterator);
+            }
+            Opcode::IterNext => {
+                let frame = self.frame_mut();
+                let iterator = frame.iterators.last_mut().expect("no loop iterator");
+                match iterator.next() {
+                    Some(item) => self.stack.push(item),
+                    None => frame.ip = operands[0],
+                }
+            }
+            Opcode::IterEnd => {
+                self.frame_mut().iterators.pop();
+            }
+            Opcode::PushScope => {
+                let frame = self.frame_mut();
+                let scope = Environment::new_scope(Rc::clone(&frame.env));
+                let outer = std::mem::replace(&mut frame.env, scope);
+                frame.scopes.push(outer);
+            }
+            Opcode::PopScope => {
+                let frame = self.frame_mut();
+                frame.env = frame.scopes.pop().expect("no loop scope");
+            }
+            Opcode::PushLoop => {
+                let height = self.stack.len();
+                self.frame_mut().loops.push(height);
+            }
+            Opcode::PopLoop => {
+                self.frame_mut().loops.pop();
+            }
+            Opcode::Unwind => {
+                let height = *self.frame().loops.last().expect("no loop");
+                self.stack.truncate(height);
+            }
+        }
+        Ok(None)
+    }
+
+    fn call_function(&mut self, argc: usize) -> Result<(), RuntimeError> {
+        let args = self.stack.split_off(self.stack.len() - argc);
+        let callee = self.pop();
+        match callee {
+            Object::Closure { function, env } => {
+                evaluator::check_arity(function.required, function.parameters.len(), argc)?;
+                // A call whose result a function returns straight away replaces
+                // that function's frame, so tail recursion does not nest.
+                if self.frames.len() > 1 && self.in_tail_position() {
+                    let frame = self.frames.pop().expect("vm has no frame");
+                    self.stack.truncate(frame.base_pointer);
+                } else if self.frames.len() > evaluator::max_call_depth() {
+                    return Err(RuntimeError::StackOverflow {
+                        limit: evaluator::max_call_depth(),
+                        span: Span::default(),
+                    });
+                }
+                let env = Environment::new_scope(env);
+                for (param, arg) in function.parameters.iter().zip(args) {
+                    env.borrow_mut().set(param.clone(), arg);
+                }
+                self.frames
+                    .push(Frame::new(function, env, self.stack.len(), argc));
+            }
+            // Builtins, functions created by the evaluator and non-callables.
+            callee => {
+                let result = evaluator::apply_function(callee, args)?;
+                self.stack.push(result);
+            }
+        }
+        Ok(())
+    }
+
+    // Whether the next instruction, after following jumps, returns from the
+    // current frame.
+    fn in_tail_position(&self) -> bool {
+        let frame = self.frame();
+        let ins = &frame.function.instructions;
+        let mut ip = frame.ip;
+        loop {
+            match Opcode::from_byte(ins[ip]) {
+                Some(Opcode::ReturnValue) => return true,
+                Some(Opcode::Jump) => ip = read_operand(2, &ins[ip + 1..]),
+                _ => return false,
+            }
+        }
+    }
+
+    fn frame(&self) -> &Frame {
+        self.frames.last().expect("vm has no frame")
+    }
+
+    fn frame_mut(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("vm has no frame")
+    }
+
+    fn pop(&mut self) -> Object {
+        self.stack.pop().expect("stack underflow")
+    }
+}
+
+fn infix_operator(op: Opcode) -> &'static str {
+    match op {
+        Opcode::Add => "+",
+        Opcode::Sub => "-",
+        Opcode::Mul => "*",
+        Opcode::Div => "/",
+        Opcode::Mod => "%",
+        Opcode::Equal => "==",
+        Opcode::NotEqual => "!=",
+        Opcode::LessThan => "<",
+        Opcode::GreaterThan => ">",
+        Opcode::LessEqual => "<=",
+        Opcode::GreaterEqual => ">=",
+        Opcode::Range => "..",
+        _ => unreachable!("{} is not an infix opcode", op.name()),
+    }
+}
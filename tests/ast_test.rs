@@ -9,31 +9,22 @@ use macros::sf;
 fn test_string() {
     let program = Program {
         statements: vec![Statement::LetStatement {
-            token: Token {
-                token_type: TokenType::LET,
-                literal: sf!("let"),
-            },
+            token: Token::new(TokenType::LET, sf!("let")),
             name: Expression::Identifier {
-                token: Token {
-                    token_type: TokenType::IDENT,
-                    literal: sf!("my_var"),
-                },
+                token: Token::new(TokenType::IDENT, sf!("my_var")),
                 value: sf!("my_var"),
             },
             value: Expression::Identifier {
-                token: Token {
-                    token_type: TokenType::IDENT,
-                    literal: sf!("another_var"),
-                },
+                token: Token::new(TokenType::IDENT, sf!("another_var")),
                 value: sf!("another_var"),
             },
         }],
     };
 
-    if program.statements.get(0).unwrap().to_string() != "let my_var = another_var;" {
+    if program.statements.first().unwrap().to_string() != "let my_var = another_var;" {
         panic!(
             "program.to_string() wrong. got={}",
-            program.statements.get(0).unwrap().to_string()
+            program.statements.first().unwrap().to_string()
         )
     }
 }
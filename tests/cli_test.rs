@@ -0,0 +1,315 @@
+use std::process::{Command as Process, Stdio};
+
+use d_lang::{
+    cli::{parse_args, Command, Options, Source},
+    repl::Backend,
+};
+use macros::sf;
+
+fn parse(args: &[&str]) -> Command {
+    match parse_args(args.iter().map(|arg| arg.to_string())) {
+        Ok(command) => command,
+        Err(err) => panic!("failed to parse {:?}: {}", args, err),
+    }
+}
+
+fn d_lang(args: &[&str], stdin: &str) -> (i32, String, String) {
+    let mut child = Process::new(env!("CARGO_BIN_EXE_d_lang"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start d_lang");
+
+    use std::io::Write;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().expect("failed to run d_lang");
+
+    (
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_parse_args() {
+    let tests: Vec<(Vec<&str>, Command)> = vec![
+        (vec![], Command::Run(Options::default())),
+        (
+            vec!["--quiet", "--vm", "script.dl", "a", "--b"],
+            Command::Run(Options {
+                source: Source::File(sf!("script.dl")),
+                script_args: vec![sf!("a"), sf!("--b")],
+                backend: Backend::Vm,
+                banner: false,
+                ..Options::default()
+            }),
+        ),
+        (
+            vec!["--no-banner", "-e", "1 + 2", "x"],
+            Command::Run(Options {
+                source: Source::Eval(sf!("1 + 2")),
+                script_args: vec![sf!("x")],
+                banner: false,
+                ..Options::default()
+            }),
+        ),
+        (
+            vec!["--print-tokens", "--print-ast", "-"],
+            Command::Run(Options {
+                source: Source::Stdin,
+                print_tokens: true,
+                print_ast: true,
+                ..Options::default()
+            }),
+        ),
+        (
+            vec!["parse", "--json", "sample.dl"],
+            Command::Parse {
+                filename: sf!("sample.dl"),
+                as_json: true,
+            },
+        ),
+        (vec!["-h"], Command::Help),
+    ];
+
+    tests.into_iter().for_each(|(args, expected)| {
+        let command = parse(&args);
+        if command != expected {
+            panic!(
+                "wrong command for {:?}. expected={:#?}, got={:#?}",
+                args, expected, command
+            );
+        }
+    });
+}
+
+#[test]
+fn test_parse_args_errors() {
+    let tests: Vec<(Vec<&str>, &str)> = vec![
+        (vec!["--frobnicate"], "unknown option `--frobnicate`"),
+        (vec!["-e"], "`-e` needs an argument"),
+        (
+            vec!["--print-ast"],
+            "`--print-tokens` and `--print-ast` need a script",
+        ),
+        (vec!["parse", "--json"], "`parse` needs a file"),
+    ];
+
+    tests.into_iter().for_each(|(args, expected)| {
+        match parse_args(args.iter().map(|arg| arg.to_string())) {
+            Err(err) if err.to_string() == expected => {}
+            result => panic!(
+                "expected error {:?} for {:?}, got={:?}",
+                expected, args, result
+            ),
+        }
+    });
+}
+
+#[test]
+fn test_exit_codes() {
+    let tests: Vec<(Vec<&str>, &str, i32)> = vec![
+        (vec!["-e", "let x = 1 + 2;"], "", 0),
+        (vec!["-e", "1 + true"], "", 1),
+        (vec!["-e", "let x 5;"], "", 1),
+        (vec!["-"], "len(args) == 2", 0),
+        (vec!["-", "a"], "if (len(args) == 1) { 5 / 0 }", 1),
+        (vec!["does_not_exist.dl"], "", 1),
+        (vec!["--frobnicate"], "", 2),
+    ];
+
+    tests.into_iter().for_each(|(args, stdin, expected)| {
+        let (code, _, stderr) = d_lang(&args, stdin);
+        if code != expected {
+            panic!(
+                "wrong exit code for {:?}. expected={}, got={}, stderr={}",
+                args, expected, code, stderr
+            );
+        }
+    });
+}
+
+#[test]
+fn test_print_tokens_and_ast() {
+    let (code, stdout, _) = d_lang(&["--print-tokens", "-e", "let x = 5;"], "");
+    let expected = "1:1 LET \"let\"
+1:5 IDENT \"x\"
+1:7 ASSIGN \"=\"
+1:9 INT \"5\"
+1:10 SEMICOLON \";\"
+1:11 EOF \"\\0\"
+";
+    if code != 0 || stdout != expected {
+        panic!("wrong tokens. expected=\n{}got=\n{}", expected, stdout);
+    }
+
+    let (code, stdout, _) = d_lang(&["--print-ast", "-e", "let x = 1 + 2 * 3;"], "");
+    if code != 0 || stdout != "let x = (1 + (2 * 3));\n" {
+        panic!("wrong ast. got={}", stdout);
+    }
+}
+
+#[test]
+fn test_script_output() {
+    let tests: Vec<(&str, &str)> = vec![
+        ("let x = 5; x * 3", ""),
+        ("let x = 5; puts(x * 3); puts(x > 1)", "15\ntrue\n"),
+        (
+            "print(\"a\", 1, puts()); puts(fn(x) { x })",
+            "a 1 nullfn(x) { x }\n",
+        ),
+        (
+            "puts([1, \"two\"], {\"k\": false})",
+            "[1, \"two\"]\n{\"k\": false}\n",
+        ),
+    ];
+
+    tests.into_iter().for_each(|(code, expected)| {
+        [&["-e", code][..], &["--vm", "-e", code][..]]
+            .into_iter()
+            .for_each(|args| {
+                let (_, stdout, stderr) = d_lang(args, "");
+                if stdout != expected {
+                    panic!(
+                        "wrong output for {:?}. expected={:?}, got={:?}, stderr={}",
+                        args, expected, stdout, stderr
+                    );
+                }
+            });
+    });
+}
+
+// Runs a prompt session on `stdin` with a fresh home directory and returns
+// the exit code, output and saved history.
+fn repl_session(stdin: &str) -> (i32, String, String, String) {
+    let home = std::env::temp_dir().join(format!(
+        "d_lang_repl_test_{}_{}",
+        std::process::id(),
+        stdin.len()
+    ));
+    std::fs::create_dir_all(&home).unwrap();
+
+    let mut child = Process::new(env!("CARGO_BIN_EXE_d_lang"))
+        .arg("-q")
+        .env("HOME", &home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start d_lang");
+
+    use std::io::Write;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().expect("failed to run d_lang");
+    let history = std::fs::read_to_string(home.join(".d_lang_history")).unwrap_or_default();
+    std::fs::remove_dir_all(&home).unwrap();
+
+    (
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+        history,
+    )
+}
+
+#[test]
+fn test_repl_multiline_input() {
+    let (code, stdout, stderr, history) =
+        repl_session("let double = fn(x) {\n  x *\n  2\n};\ndouble(21)\n");
+
+    if code != 0 {
+        panic!("repl did not exit cleanly on EOF, got={}", code);
+    }
+    if !stdout.contains("42") || !stderr.is_empty() {
+        panic!(
+            "wrong repl output. stdout={:?}, stderr={:?}",
+            stdout, stderr
+        );
+    }
+    if !history.contains("double(21)") {
+        panic!("history was not saved, got={:?}", history);
+    }
+}
+
+#[test]
+fn test_repl_echo() {
+    let (code, stdout, stderr, _) =
+        repl_session("let x = 5;\nlet s = \"a\\tb\"\ns\n[s, 1]\n{\"k\": s}\nputs(s)\nx\n");
+
+    let expected = [
+        "\"a\\tb\"",
+        "[\"a\\tb\", 1]",
+        "{\"k\": \"a\\tb\"}",
+        "a\tb",
+        "5",
+    ];
+    let stdout = stdout.replace(">>> ", "");
+    if code != 0 || stdout.trim_end() != expected.join("\n") {
+        panic!(
+            "wrong echo. expected=\n{}\ngot=\n{}\nstderr={}",
+            expected.join("\n"),
+            stdout,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn test_repl_commands() {
+    let script = std::env::temp_dir().join(format!("d_lang_load_{}.dl", std::process::id()));
+    std::fs::write(&script, "let loaded = fn() { 1 };").unwrap();
+
+    let (code, stdout, stderr, _) = repl_session(&format!(
+        ":type 1 + 2
+:type let tmp = [1]; tmp
+let x = 5;
+:type x = \"five\"
+:type x
+:load {}
+:env
+:ast -x * 2
+:tokens x;
+:reset
+:env
+:frob
+:quit
+puts(\"not reached\")
+",
+        script.display()
+    ));
+    std::fs::remove_file(&script).unwrap();
+
+    let expected = [
+        "INTEGER",
+        "ARRAY",
+        "STRING",
+        "INTEGER",
+        "loaded: FUNCTION\nx: INTEGER",
+        "((-x) * 2)",
+        "1:1 IDENT \"x\"\n1:2 SEMICOLON \";\"\n1:3 EOF \"\\0\"",
+    ];
+    let stdout = stdout.replace(">>> ", "");
+    if code != 0 || stdout.trim_end() != expected.join("\n") {
+        panic!(
+            "wrong command output. expected=\n{}\ngot=\n{}",
+            expected.join("\n"),
+            stdout
+        );
+    }
+    if stderr.trim_end() != "error: unknown command `:frob`, see :help" {
+        panic!("wrong command errors, got={:?}", stderr);
+    }
+}
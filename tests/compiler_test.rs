@@ -0,0 +1,224 @@
+use d_lang::{
+    ast::{Expression, Program, Statement},
+    compiler::{
+        code::{disassemble, make, read_operands, Opcode},
+        compile, CompileError,
+    },
+    lexer::Lexer,
+    object::Object,
+    parser::Parser,
+    token::{Token, TokenType},
+};
+use macros::sf;
+
+#[test]
+fn test_make() {
+    let tests: Vec<(Opcode, Vec<usize>, Vec<u8>)> = vec![
+        (
+            Opcode::Constant,
+            vec![65534],
+            vec![Opcode::Constant as u8, 255, 254],
+        ),
+        (Opcode::Call, vec![255], vec![Opcode::Call as u8, 255]),
+        (Opcode::Add, vec![], vec![Opcode::Add as u8]),
+        (
+            Opcode::Default,
+            vec![1, 258],
+            vec![Opcode::Default as u8, 1, 1, 2],
+        ),
+    ];
+
+    tests.iter().for_each(|(op, operands, expected)| {
+        let instruction = make(*op, operands).unwrap();
+        if instruction != *expected {
+            panic!(
+                "wrong encoding for {}. expected={:?}, got={:?}",
+                op.name(),
+                expected,
+                instruction
+            );
+        }
+
+        let (read, n) = read_operands(*op, &instruction[1..]);
+        if read != *operands || n != instruction.len() - 1 {
+            panic!("wrong operands for {}. got={:?}", op.name(), read);
+        }
+    });
+}
+
+#[test]
+fn test_compile_infix_and_conditionals() {
+    let input: Vec<char> = "if (true) { 10 } else { 1 + 2 }; 3333;".chars().collect();
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+
+    let function = compile(&program.statements).unwrap();
+
+    let expected = "0000 OpTrue
+0001 OpJumpNotTruthy 10
+0004 OpConstant 0
+0007 OpJump 17
+0010 OpConstant 1
+0013 OpConstant 2
+0016 OpAdd
+0017 OpPop
+0018 OpConstant 3
+0021 OpReturnValue
+";
+    let got = disassemble(&function.instructions);
+    if got != expected {
+        panic!("wrong instructions. expected=\n{}got=\n{}", expected, got);
+    }
+
+    let constants: Vec<i64> = function
+        .constants
+        .iter()
+        .map(|c| match c {
+            Object::Integer { value } => *value,
+            _ => panic!("constant is not Integer. got={:#?}", c),
+        })
+        .collect();
+    if constants != vec![10, 1, 2, 3333] {
+        panic!("wrong constants. got={:?}", constants);
+    }
+}
+
+#[test]
+fn test_compile_functions() {
+    let input: Vec<char> = "let add = fn(a, b = 2) { a + b }; add(1);"
+        .chars()
+        .collect();
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+
+    let function = compile(&program.statements).unwrap();
+
+    let expected = "0000 OpClosure 0
+0003 OpDefine 0
+0006 OpPop
+0007 OpGetName 0
+0010 OpConstant 0
+0013 OpCall 1
+0015 OpReturnValue
+";
+    let got = disassemble(&function.instructions);
+    if got != expected {
+        panic!("wrong instructions. expected=\n{}got=\n{}", expected, got);
+    }
+
+    let add = function.functions.first().expect("no compiled function");
+    let expected = "0000 OpDefault 1 11
+0004 OpConstant 0
+0007 OpDefine 0
+0010 OpPop
+0011 OpGetName 1
+0014 OpGetName 0
+0017 OpAdd
+0018 OpReturnValue
+";
+    let got = disassemble(&add.instructions);
+    if got != expected {
+        panic!("wrong instructions. expected=\n{}got=\n{}", expected, got);
+    }
+
+    if add.parameters != vec!["a", "b"] || add.required != 1 {
+        panic!(
+            "wrong parameters. got={:?}, required={}",
+            add.parameters, add.required
+        );
+    }
+}
+
+#[test]
+fn test_make_operand_out_of_range() {
+    let tests: Vec<(Opcode, Vec<usize>, usize)> = vec![
+        (Opcode::Constant, vec![65536], 65535),
+        (Opcode::Call, vec![256], 255),
+        (Opcode::Default, vec![256, 0], 255),
+    ];
+
+    tests
+        .iter()
+        .for_each(|(op, operands, max)| match make(*op, operands) {
+            Err(CompileError::OperandOutOfRange { max: got, .. }) if got == *max => {}
+            result => panic!(
+                "{} {:?} was not rejected. got={:?}",
+                op.name(),
+                operands,
+                result
+            ),
+        });
+}
+
+#[test]
+fn test_compile_errors() {
+    let args = vec!["1"; 256].join(", ");
+    match compile(&parse(&format!("f({});", args)).statements) {
+        Err(CompileError::OperandOutOfRange {
+            operand: 256,
+            max: 255,
+            span,
+            ..
+        }) if span.column == 1 => {}
+        result => panic!("256 arguments were not rejected. got={:?}", result.err()),
+    }
+
+    let constants = "1;".repeat(65537);
+    match compile(&parse(&constants).statements) {
+        Err(CompileError::OperandOutOfRange {
+            operand: 65536,
+            max: 65535,
+            ..
+        }) => {}
+        result => panic!("65537 constants were not rejected. got={:?}", result.err()),
+    }
+
+    let mut program = parse("1 + 2; -3;");
+    if let Statement::ExpressionStatement {
+        expression: Expression::Infix { operator, .. },
+        ..
+    } = &mut program.statements[0]
+    {
+        *operator = sf!("^");
+    }
+    match compile(&program.statements) {
+        Err(CompileError::UnknownOperator { operator, .. }) if operator == "^" => {}
+        result => panic!(
+            "unknown infix operator was compiled. got={:?}",
+            result.err()
+        ),
+    }
+
+    let mut program = parse("-3;");
+    if let Statement::ExpressionStatement {
+        expression: Expression::Prefix { operator, .. },
+        ..
+    } = &mut program.statements[0]
+    {
+        *operator = sf!("~");
+    }
+    match compile(&program.statements) {
+        Err(CompileError::UnknownOperator { operator, .. }) if operator == "~" => {}
+        result => panic!(
+            "unknown prefix operator was compiled. got={:?}",
+            result.err()
+        ),
+    }
+
+    let program = vec![Statement::ExpressionStatement {
+        token: Token::new(TokenType::SEMICOLON, sf!(";")),
+        expression: Expression::NoExpression,
+    }];
+    match compile(&program) {
+        Err(CompileError::MissingExpression { .. }) => {}
+        result => panic!("missing expression was compiled. got={:?}", result.err()),
+    }
+}
+
+fn parse(input: &str) -> Program<Statement> {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    p.parse_program().unwrap()
+}
@@ -0,0 +1,81 @@
+use std::{cell::RefCell, rc::Rc};
+
+use d_lang::{
+    diagnostics::Diagnostic, environment::Environment, evaluator::eval_statements, lexer::Lexer,
+    parser::Parser,
+};
+
+#[test]
+fn test_render_runtime_error() {
+    let input = "let a = 5;\nlet b = a + true;";
+
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+    let env = Rc::new(RefCell::new(Environment::new()));
+
+    let err = match eval_statements(program.statements, &env) {
+        Err(err) => err,
+        Ok(evaluated) => panic!("No error returned. got={:#?}", evaluated),
+    };
+
+    let expected = "error: type mismatch: INTEGER + BOOLEAN
+ --> main.dl:2:9
+  |
+2 | let b = a + true;
+  |         ^^^^^^^^
+";
+    let rendered = Diagnostic::from(&err).render(input, "main.dl");
+    if rendered != expected {
+        panic!(
+            "wrong rendering. expected=\n{}\ngot=\n{}",
+            expected, rendered
+        );
+    }
+}
+
+#[test]
+fn test_render_innermost_error_span() {
+    let input = "let f = fn(x) { x + y };\nf(1) * 2;";
+
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+    let env = Rc::new(RefCell::new(Environment::new()));
+
+    match eval_statements(program.statements, &env) {
+        Err(err) => {
+            let span = err.span();
+            if span.line != 1 || span.column != 21 || span.len != 1 {
+                panic!("error does not point at `y`. got={:#?}", span);
+            }
+        }
+        Ok(evaluated) => panic!("No error returned. got={:#?}", evaluated),
+    }
+}
+
+#[test]
+fn test_render_parse_error() {
+    let input = "let x = 1;\n\tlet y 2;";
+
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let errors = match p.parse_program() {
+        Ok(_) => panic!("expected parse errors"),
+        Err(errors) => errors,
+    };
+
+    let expected = "error: expected token ASSIGN, got INT `2`
+ --> <stdin>:2:8
+  |
+2 | \tlet y 2;
+  | \t      ^
+";
+    let rendered = Diagnostic::from(errors.first().unwrap()).render(input, "<stdin>");
+    if rendered != expected {
+        panic!(
+            "wrong rendering. expected=\n{}\ngot=\n{}",
+            expected, rendered
+        );
+    }
+}
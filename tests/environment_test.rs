@@ -0,0 +1,59 @@
+use std::{cell::RefCell, rc::Rc};
+
+use d_lang::{
+    ast::Statement,
+    compiler::compile,
+    environment::{collect, Environment},
+    evaluator::eval_statements,
+    lexer::Lexer,
+    parser::Parser,
+    vm,
+};
+
+const CALLS: &str = "let f = fn() { let g = fn() { 1 }; g() };
+for i in 0..5000 { f() }";
+
+#[test]
+fn test_call_scopes_are_freed() {
+    let env = Rc::new(RefCell::new(Environment::new()));
+    eval_statements(parse(CALLS), &env).unwrap();
+    assert_scopes_freed("evaluator", &env);
+
+    let env = Rc::new(RefCell::new(Environment::new()));
+    vm::run(Rc::new(compile(&parse(CALLS)).unwrap()), &env).unwrap();
+    assert_scopes_freed("vm", &env);
+}
+
+#[test]
+fn test_escaped_functions_keep_their_scope() {
+    let input = "let make = fn(n) { let get = fn() { n }; get };
+    let first = make(7);
+    for i in 0..3000 { make(i) }";
+    let env = Rc::new(RefCell::new(Environment::new()));
+    eval_statements(parse(input), &env).unwrap();
+    collect();
+
+    let value = eval_statements(parse("first()"), &env).unwrap();
+    if value.to_string() != "7" {
+        panic!("wrong value from the kept scope. got={}", value);
+    }
+}
+
+fn parse(input: &str) -> Vec<Statement> {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    p.parse_program().unwrap().statements
+}
+
+// Every call scope holds `g`, which refers back to it, and points out to
+// `env`. Once they are gone only `env` itself and `f` are left.
+fn assert_scopes_freed(backend: &str, env: &Rc<RefCell<Environment>>) {
+    collect();
+    if Rc::strong_count(env) != 2 {
+        panic!(
+            "call scopes leaked on the {}. references to env={}",
+            backend,
+            Rc::strong_count(env)
+        );
+    }
+}
@@ -1,5 +1,11 @@
+use std::{cell::RefCell, rc::Rc};
+
 use d_lang::{
-    ast::Node, environment::Environment, evaluator::eval_statements, lexer::Lexer, object::Object,
+    ast::Node,
+    environment::Environment,
+    evaluator::{eval_statements, set_max_call_depth, RuntimeError, DEFAULT_MAX_CALL_DEPTH},
+    lexer::Lexer,
+    object::{HashKey, Object},
     parser::Parser,
 };
 use macros::sf;
@@ -22,8 +28,16 @@ fn test_eval_int_expr() {
         sf!("3 * 3 * 3 + 10"),
         sf!("3 * (3 * 3) + 10"),
         sf!("(5 + 10 * 2 + 15 / 3) * 2 + -10"),
+        sf!("0xFF + 0o10 + 0b11 + 1_000"),
+        sf!("-0x7F"),
+        sf!("17 % 5"),
+        sf!("-17 % 5"),
+        sf!("2 + 10 % 4 * 3"),
+        sf!("-9223372036854775808 % 10"),
+    ];
+    let results: Vec<i64> = vec![
+        5, 10, -5, -10, 10, 32, 0, 20, 25, 0, 60, 30, 37, 37, 50, 1266, -127, 2, -2, 8, -8,
     ];
-    let results: Vec<i64> = vec![5, 10, -5, -10, 10, 32, 0, 20, 25, 0, 60, 30, 37, 37, 50];
 
     results.iter().enumerate().for_each(|(i, r)| {
         let evaluated = test_eval(input.get(i).unwrap().clone());
@@ -53,10 +67,17 @@ fn test_eval_bool_expr() {
         sf!("(1 < 2) == false"),
         sf!("(1 > 2) == true"),
         sf!("(1 > 2) == false"),
+        sf!("1 <= 1"),
+        sf!("2 <= 1"),
+        sf!("1 >= 1"),
+        sf!("1 >= 2"),
+        sf!("1.5 <= 2"),
+        sf!("3 >= 2.5"),
+        sf!("(1 <= 2) == (2 >= 1)"),
     ];
     let results: Vec<bool> = vec![
         true, false, true, false, false, false, true, false, false, true, true, true, false, true,
-        true, true, false, false, true,
+        true, true, false, false, true, true, false, true, false, true, true, true,
     ];
 
     results.iter().enumerate().for_each(|(i, r)| {
@@ -142,6 +163,11 @@ fn test_errors() {
                 return 1;
             }"),
         sf!("foobar;"),
+        sf!("\"Hello\" - \"World\""),
+        sf!("\"Hello\" + 5"),
+        sf!("5[0]"),
+        sf!("{\"name\": \"d_lang\"}[fn(x) { x }];"),
+        sf!("{fn(x) { x }: 1};"),
     ];
     let results: Vec<&str> = vec![
         "type mismatch: INTEGER + BOOLEAN",
@@ -152,20 +178,144 @@ fn test_errors() {
         "unknown operator: BOOLEAN + BOOLEAN",
         "unknown operator: BOOLEAN + BOOLEAN",
         "identifier not found: foobar",
+        "unknown operator: STRING - STRING",
+        "type mismatch: STRING + INTEGER",
+        "index operator not supported: INTEGER[INTEGER]",
+        "unusable as hash key: FUNCTION",
+        "unusable as hash key: FUNCTION",
     ];
 
     results.iter().enumerate().for_each(|(i, r)| {
-        let evaluated = test_eval(input.get(i).unwrap().clone());
-        if let Object::Error { message } = evaluated {
-            if *r != message {
-                panic!("wrong error message. expected={}, got={}", *r, message);
-            }
-        } else {
-            panic!("No error object returned. got={:#?}", evaluated);
+        let err = test_eval_error(input.get(i).unwrap().clone());
+        if *r != err.to_string() {
+            panic!("wrong error message. expected={}, got={}", *r, err);
+        }
+    });
+}
+
+#[test]
+fn test_float_arithmetic() {
+    let input: Vec<String> = vec![
+        sf!("3.5"),
+        sf!("-2.5"),
+        sf!("1.5 + 2.25"),
+        sf!("1 + 0.5"),
+        sf!("0.5 * 4"),
+        sf!("7 / 2.0"),
+        sf!("1e3 - 1"),
+        sf!("let x = 1; x += 0.25; x"),
+    ];
+    let results: Vec<f64> = vec![3.5, -2.5, 3.75, 1.5, 2.0, 3.5, 999.0, 1.25];
+
+    results
+        .iter()
+        .enumerate()
+        .for_each(|(i, r)| match test_eval(input.get(i).unwrap().clone()) {
+            Object::Float { value } if value == *r => {}
+            evaluated => panic!("object is not {:?}, got={:#?}", r, evaluated),
+        });
+
+    let comparisons = [
+        ("1 == 1.0", true),
+        ("0.1 + 0.2 == 0.3", false),
+        ("2 < 2.5", true),
+        ("2.5 > 3", false),
+        ("1.5 != 1.5", false),
+    ];
+    comparisons.iter().for_each(|(input, expected)| {
+        test_bool_obj(test_eval(sf!(*input)), *expected);
+    });
+
+    let displayed = test_eval(sf!("[7 / 2, 6 / 2.0, 1e-9, 2.5e20, 7.5 % 2]")).to_string();
+    if displayed != "[3, 3.0, 1e-9, 2.5e20, 1.5]" {
+        panic!("wrong display, got={}", displayed);
+    }
+}
+
+#[test]
+fn test_float_errors() {
+    let tests = [
+        ("1.5 / 0", "division by zero"),
+        ("1 / 0.0", "division by zero"),
+        ("1.5 + true", "type mismatch: FLOAT + BOOLEAN"),
+        ("\"a\" + 1.5", "type mismatch: STRING + FLOAT"),
+        ("0.5..2", "unknown operator: FLOAT .. INTEGER"),
+        ("{1.5: 1}", "unusable as hash key: FLOAT"),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let err = test_eval_error(sf!(*input));
+        if err.to_string() != *expected {
+            panic!(
+                "expected error {:?} for {:?}, got={:?}",
+                expected, input, err
+            );
         }
     });
 }
 
+#[test]
+fn test_division_by_zero() {
+    let err = test_eval_error(sf!("let zero = 0; 10 / zero;"));
+
+    match err {
+        RuntimeError::DivisionByZero { span } => {
+            if span.line != 1 || span.column != 15 || span.len != 9 {
+                panic!("wrong span. got={:#?}", span);
+            }
+        }
+        _ => panic!("error is not DivisionByZero. got={:#?}", err),
+    }
+
+    ["let zero = 0; 10 % zero;", "10 % 0.0", "0x7F % 0"]
+        .iter()
+        .for_each(|input| match test_eval_error(sf!(*input)) {
+            RuntimeError::DivisionByZero { .. } => {}
+            err => panic!("error is not DivisionByZero for {}. got={:#?}", input, err),
+        });
+}
+
+#[test]
+fn test_integer_overflow() {
+    let tests = [
+        (
+            "9223372036854775807 + 1",
+            "integer overflow: 9223372036854775807 + 1",
+        ),
+        (
+            "let m = -9223372036854775807 - 1; m - 1",
+            "integer overflow: -9223372036854775808 - 1",
+        ),
+        (
+            "9223372036854775807 * 2",
+            "integer overflow: 9223372036854775807 * 2",
+        ),
+        (
+            "let m = -9223372036854775807 - 1; m / -1",
+            "integer overflow: -9223372036854775808 / -1",
+        ),
+        (
+            "let m = -9223372036854775807 - 1; m % -1",
+            "integer overflow: -9223372036854775808 % -1",
+        ),
+        (
+            "let m = -9223372036854775807 - 1; -m",
+            "integer overflow: -(-9223372036854775808)",
+        ),
+        (
+            "let x = 9223372036854775807; x += 1",
+            "integer overflow: 9223372036854775807 + 1",
+        ),
+    ];
+
+    tests
+        .iter()
+        .for_each(|(input, expected)| match test_eval_error(sf!(*input)) {
+            err @ RuntimeError::IntegerOverflow { .. } if err.to_string() == *expected => {}
+            err => panic!("expected {:?} for {}. got={:#?}", expected, input, err),
+        });
+}
+
 #[test]
 fn test_let_statements() {
     let input: Vec<String> = vec![
@@ -173,8 +323,9 @@ fn test_let_statements() {
         sf!("let a = 5 * 5; a;"),
         sf!("let a = 5; let b = a; b;"),
         sf!("let a = 5; let b = a; let c = a + b + 5; c;"),
+        sf!("let x1 = 5; let größe = x1 * 2; let 変数 = größe + 1; 変数;"),
     ];
-    let results: Vec<i64> = vec![5, 25, 5, 15];
+    let results: Vec<i64> = vec![5, 25, 5, 15, 11];
 
     results.iter().enumerate().for_each(|(i, r)| {
         let evaluated = test_eval(input.get(i).unwrap().clone());
@@ -229,23 +380,607 @@ fn test_function_calls() {
     });
 }
 
-fn test_eval(input: String) -> Object {
+#[test]
+fn test_default_parameters() {
+    let input: Vec<String> = vec![
+        sf!("let add = fn(a, b = 10) { a + b }; add(1);"),
+        sf!("let add = fn(a, b = 10) { a + b }; add(1, 2);"),
+        sf!("let f = fn(a, b = a * 2) { a + b }; f(3);"),
+        sf!("let n = 4; let f = fn(a = n) { a }; f();"),
+    ];
+    let results: Vec<i64> = vec![11, 3, 9, 4];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_function_arity_errors() {
+    let input: Vec<String> = vec![
+        sf!("fn(a, b) { a + b }(1)"),
+        sf!("fn(a) { a }(1, 2)"),
+        sf!("fn(a, b = 1) { a + b }()"),
+        sf!("fn(a, b = 1) { a + b }(1, 2, 3)"),
+    ];
+    let results: Vec<&str> = vec![
+        "wrong number of arguments: want=2, got=1",
+        "wrong number of arguments: want=1, got=2",
+        "wrong number of arguments: want=1, got=0",
+        "wrong number of arguments: want=2, got=3",
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let err = test_eval_error(input.get(i).unwrap().clone());
+        if *r != err.to_string() {
+            panic!("wrong error message. expected={}, got={}", *r, err);
+        }
+    });
+}
+
+#[test]
+fn test_closures() {
+    let input: Vec<String> = vec![
+        sf!("let new_adder = fn(x) { fn(y) { x + y } };
+        let add_two = new_adder(2);
+        add_two(3);"),
+        sf!("let x = 10;
+        let f = fn(x) { x * 2 };
+        f(3);
+        x;"),
+        sf!("let fact = fn(x) {
+            if x == 1 {
+                return 1;
+            }
+            return x * fact(x - 1);
+        };
+        fact(5);"),
+        sf!("let a = 1;
+        let get_a = fn() { a };
+        let shadow = fn() { let a = 2; get_a() };
+        shadow();"),
+    ];
+    let results: Vec<i64> = vec![5, 10, 120, 1];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_tail_calls() {
+    let input: Vec<String> = vec![
+        sf!(
+            "let countdown = fn(n) { if (n == 0) { return 0; } return countdown(n - 1); };
+        countdown(100000);"
+        ),
+        sf!(
+            "let sum = fn(n, acc) { if (n == 0) { acc } else { sum(n - 1, acc + n) } };
+        sum(10000, 0);"
+        ),
+        sf!(
+            "let even = fn(n) { if (n == 0) { return true; } odd(n - 1) };
+        let odd = fn(n) { if (n == 0) { return false; } even(n - 1) };
+        if (even(10001)) { 1 } else { 0 };"
+        ),
+    ];
+    let results: Vec<i64> = vec![0, 50005000, 0];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_stack_overflow() {
+    let err = test_eval_error(sf!("let f = fn(n) { 1 + f(n + 1) }; f(0);"));
+
+    match err {
+        RuntimeError::StackOverflow { limit, .. } if limit == DEFAULT_MAX_CALL_DEPTH => {}
+        _ => panic!("error is not StackOverflow. got={:#?}", err),
+    }
+
+    let evaluated = test_eval(sf!(
+        "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } };
+    f(5000);"
+    ));
+    test_int_obj(evaluated, 5000);
+
+    set_max_call_depth(10);
+    let err = test_eval_error(sf!(
+        "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } };
+    f(10);"
+    ));
+    if err.to_string() != "stack overflow: call depth exceeded 10" {
+        panic!("wrong error message. got={}", err);
+    }
+    let evaluated = test_eval(sf!(
+        "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } };
+    f(9);"
+    ));
+    test_int_obj(evaluated, 9);
+    set_max_call_depth(DEFAULT_MAX_CALL_DEPTH);
+}
+
+#[test]
+fn test_while_loops() {
+    let input: Vec<String> = vec![
+        sf!("let i = 0; while (i < 10) { let i = i + 1; } i"),
+        sf!("let i = 0; while (true) { if (i == 5) { break; } let i = i + 1; } i"),
+        sf!("let i = 0; let sum = 0;
+        while (i < 10) {
+            let i = i + 1;
+            if (i == 3) { continue; }
+            let sum = sum + i;
+        }
+        sum"),
+        sf!("let n = 0; let i = 0;
+        while (i < 3) {
+            let i = i + 1;
+            let j = 0;
+            while (true) {
+                if (j == 2) { break; }
+                let j = j + 1;
+                let n = n + 1;
+            }
+        }
+        n"),
+        sf!("let f = fn() {
+            let i = 0;
+            while (true) {
+                if (i == 4) { return i * 10; }
+                let i = i + 1;
+            }
+            99
+        };
+        f()"),
+        sf!("let f = fn(x) { while (x > 0) { return x; } 0 }; f(3) + f(0)"),
+        sf!("let i = 0; while (i < 100000) { let i = i + 1; } i"),
+        sf!("while (false) { 1 }"),
+        sf!("while (true) { break; }"),
+    ];
+    let results: Vec<Option<i64>> = vec![
+        Some(10),
+        Some(5),
+        Some(52),
+        Some(6),
+        Some(40),
+        Some(3),
+        Some(100000),
+        None,
+        None,
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_opt_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_for_loops() {
+    let input: Vec<String> = vec![
+        sf!("let f = fn(xs) { for x in xs { if (x > 2) { return x; } } 0 }; f([1, 2, 3, 4])"),
+        sf!("let f = fn(n) { for i in 0..n { if (i * i > 50) { return i; } } -1 }; f(100)"),
+        sf!(
+            "let f = fn(h) { for k in h { if (h[k] == 2) { return k; } } 0 };
+        f({3: 2, 1: 5, 2: 2})"
+        ),
+        sf!("let f = fn() { for i in 0..10 { if (i < 5) { continue; } return i; } }; f()"),
+        sf!("let f = fn() { for i in 0..10 { if (i == 3) { break; } return i; } }; f()"),
+        sf!("let f = fn() { for i in 5..0 { return 1; } 2 }; f()"),
+        sf!("let f = fn() { for i in 0..3 { if (i == 1) { return fn() { i }; } } }; f()()"),
+        sf!("let x = 10; for x in [1, 2] { let y = x; } x"),
+        sf!("for x in [1, 2] { x }"),
+        sf!("let i = 0; while (true) { for j in 0..3 { if (j == 1) { break; } } break; } 4"),
+    ];
+    let results: Vec<Option<i64>> = vec![
+        Some(3),
+        Some(8),
+        Some(2),
+        Some(5),
+        Some(0),
+        Some(2),
+        Some(1),
+        Some(10),
+        None,
+        Some(4),
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_opt_int_obj(evaluated, *r);
+    });
+
+    match test_eval(sf!("0..3")) {
+        obj @ Object::Range { start: 0, end: 3 } if obj.to_string() == "0..3" => {}
+        evaluated => panic!("object is not 0..3, got={:#?}", evaluated),
+    }
+}
+
+#[test]
+fn test_for_loop_errors() {
+    let tests = [
+        ("for x in [1] { let y = 2; } y", "identifier not found: y"),
+        ("for x in [1] { } x", "identifier not found: x"),
+        ("for x in 5 { }", "cannot iterate over INTEGER"),
+        ("for x in \"abc\" { }", "cannot iterate over STRING"),
+        ("1..true", "type mismatch: INTEGER .. BOOLEAN"),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let err = test_eval_error(sf!(*input));
+        if err.to_string() != *expected {
+            panic!(
+                "expected error {:?} for {:?}, got={:?}",
+                expected, input, err
+            );
+        }
+    });
+
+    let err = test_eval_error(sf!("for x in 5 { }"));
+    if err.span().column != 10 {
+        panic!("wrong span. got={:#?}", err.span());
+    }
+}
+
+#[test]
+fn test_assignment() {
+    let input: Vec<String> = vec![
+        sf!("let x = 1; x = 5; x"),
+        sf!("let x = 1; x = 5"),
+        sf!("let x = 10; x += 5; x -= 3; x *= 2; x /= 4"),
+        sf!("let a = 1; let b = 2; a = b = 3; a + b"),
+        sf!("let s = 0; for i in 0..10 { s += i } s"),
+        sf!("let n = 0; while (n < 5) { n = n + 1 } n"),
+        sf!("let x = 1; let f = fn() { x = 7 }; f(); x"),
+        sf!("let x = 1; let f = fn() { let x = 2; x = 7 }; f(); x"),
+        sf!("let counter = fn() { let c = 0; fn() { c += 1 } };
+        let next = counter(); next(); next(); next()"),
+    ];
+    let results: Vec<i64> = vec![5, 5, 6, 6, 45, 5, 7, 1, 3];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+
+    match test_eval(sf!("let s = \"a\"; s += \"b\"")) {
+        Object::String { value } if value == "ab" => {}
+        evaluated => panic!("object is not \"ab\", got={:#?}", evaluated),
+    }
+}
+
+#[test]
+fn test_assignment_errors() {
+    let tests = [
+        ("x = 1", "assignment to undeclared variable: x"),
+        ("y += 1", "identifier not found: y"),
+        (
+            "let f = fn() { let z = 1; }; f(); z = 2",
+            "assignment to undeclared variable: z",
+        ),
+        ("let x = 1; x += true", "type mismatch: INTEGER + BOOLEAN"),
+        ("let x = 1; x /= 0", "division by zero"),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let err = test_eval_error(sf!(*input));
+        if err.to_string() != *expected {
+            panic!(
+                "expected error {:?} for {:?}, got={:?}",
+                expected, input, err
+            );
+        }
+    });
+
+    let err = test_eval_error(sf!("let a = 1; b = a"));
+    if err.span().column != 12 {
+        panic!("wrong span. got={:#?}", err.span());
+    }
+}
+
+#[test]
+fn test_if_block_does_not_return() {
+    let input: Vec<String> = vec![
+        sf!("if (true) { 1 }; 2"),
+        sf!("let f = fn(x) { if (x > 0) { x }; 0 }; f(5)"),
+        sf!("let f = fn(x) { if (x > 0) { if (true) { return x; } }; 0 }; f(5)"),
+        sf!("let x = if (true) { 1 } else { 2 }; x + 1"),
+    ];
+    let results: Vec<i64> = vec![2, 0, 5, 2];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_control_flow_inside_expressions() {
+    let input: Vec<String> = vec![
+        sf!("let n = 0; let f = fn(a, b) { n += 1 };
+        for x in [1, 2, 3] { f(x, if (x == 2) { break; }) }
+        n"),
+        sf!("let y = 0; for x in [1, 2, 3] { y = if (x == 2) { break; } else { x } } y"),
+        sf!("let n = 0; for c in [true, false, false] { n = 1 + if (c) { continue; } else { n } } n"),
+        sf!("let i = 0; while (if (i == 2) { break; } else { true }) { i += 1 } i"),
+        sf!("let i = 0; while (if (i < 3) { i += 1; continue; } else { false }) { i = 10 } i"),
+        sf!("let f = fn() { let a = [1, if (true) { return 7; }]; 0 }; f()"),
+        sf!("fn() { return 1 + if (true) { return 7; } else { 1 } }()"),
+        sf!("fn() { [1, if (true) { return 7; }] }()"),
+        sf!("fn() { return if (true) { return 7; } else { 1 } }() + 1"),
+    ];
+    let results: Vec<i64> = vec![1, 1, 2, 2, 3, 7, 7, 7, 8];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_function_let_does_not_leak() {
+    let err = test_eval_error(sf!("let f = fn() { let inner = 5; inner };
+    f();
+    inner;"));
+
+    match err {
+        RuntimeError::UnknownIdentifier { name, .. } if name == "inner" => {}
+        _ => panic!("wrong error. got={:#?}", err),
+    }
+}
+
+#[test]
+fn test_strings() {
+    let input: Vec<String> = vec![
+        sf!("\"Hello World!\""),
+        sf!("\"Hello\" + \" \" + \"World!\""),
+        sf!("let greet = fn(name) { \"Hi, \" + name }; greet(\"d_lang\");"),
+    ];
+    let results: Vec<&str> = vec!["Hello World!", "Hello World!", "Hi, d_lang"];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        match evaluated {
+            Object::String { value } => {
+                if value != *r {
+                    panic!("value is not {}, got={}", r, value);
+                }
+            }
+            _ => panic!("object is not string, got={}", evaluated.get_type()),
+        }
+    });
+
+    let input: Vec<String> = vec![
+        sf!("\"a\" == \"a\""),
+        sf!("\"a\" == \"b\""),
+        sf!("\"a\" != \"b\""),
+        sf!("\"a\" != \"a\""),
+    ];
+    let results: Vec<bool> = vec![true, false, true, false];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_bool_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_array_literals() {
+    let evaluated = test_eval(sf!("[1, 2 * 2, 3 + 3]"));
+    let elements = match evaluated {
+        Object::Array { elements } => elements,
+        _ => panic!("object is not array, got={}", evaluated.get_type()),
+    };
+
+    if elements.len() != 3 {
+        panic!("array has wrong num of elements. got={}", elements.len());
+    }
+
+    elements
+        .into_iter()
+        .zip([1, 4, 6])
+        .for_each(|(elem, r)| test_int_obj(elem, r));
+}
+
+#[test]
+fn test_array_index_expr() {
+    let input: Vec<String> = vec![
+        sf!("[1, 2, 3][0]"),
+        sf!("[1, 2, 3][1]"),
+        sf!("[1, 2, 3][2]"),
+        sf!("let i = 0; [1][i];"),
+        sf!("[1, 2, 3][1 + 1];"),
+        sf!("let myArray = [1, 2, 3]; myArray[2];"),
+        sf!("let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2];"),
+        sf!("let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i]"),
+        sf!("[1, 2, 3][3]"),
+        sf!("[1, 2, 3][-1]"),
+        sf!("[1, 2, 3][-3]"),
+        sf!("[1, 2, 3][-4]"),
+    ];
+    let results: Vec<Option<i64>> = vec![
+        Some(1),
+        Some(2),
+        Some(3),
+        Some(1),
+        Some(3),
+        Some(3),
+        Some(6),
+        Some(2),
+        None,
+        Some(3),
+        Some(1),
+        None,
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_opt_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_hash_literals() {
+    let evaluated = test_eval(sf!("let two = \"two\";
+    {
+        \"one\": 10 - 9,
+        two: 1 + 1,
+        \"thr\" + \"ee\": 6 / 2,
+        4: 4,
+        true: 5,
+        false: 6
+    }"));
+    let pairs = match evaluated {
+        Object::Hash { pairs } => pairs,
+        _ => panic!("object is not hash, got={}", evaluated.get_type()),
+    };
+
+    let expected: Vec<(HashKey, i64)> = vec![
+        (HashKey::String(sf!("one")), 1),
+        (HashKey::String(sf!("two")), 2),
+        (HashKey::String(sf!("three")), 3),
+        (HashKey::Integer(4), 4),
+        (HashKey::Boolean(true), 5),
+        (HashKey::Boolean(false), 6),
+    ];
+
+    if pairs.len() != expected.len() {
+        panic!("hash has wrong num of pairs. got={}", pairs.len());
+    }
+
+    expected
+        .into_iter()
+        .for_each(|(key, r)| match pairs.get(&key) {
+            Some((_, value)) => test_int_obj(value.clone(), r),
+            None => panic!("no pair for given key in pairs. key={:#?}", key),
+        });
+}
+
+#[test]
+fn test_hash_index_expr() {
+    let input: Vec<String> = vec![
+        sf!("{\"foo\": 5}[\"foo\"]"),
+        sf!("{\"foo\": 5}[\"bar\"]"),
+        sf!("let key = \"foo\"; {\"foo\": 5}[key]"),
+        sf!("{}[\"foo\"]"),
+        sf!("{5: 5}[5]"),
+        sf!("{true: 5}[true]"),
+        sf!("{false: 5}[false]"),
+    ];
+    let results: Vec<Option<i64>> = vec![Some(5), None, Some(5), None, Some(5), Some(5), Some(5)];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_opt_int_obj(evaluated, *r);
+    });
+}
+
+#[test]
+fn test_builtin_functions() {
+    let input: Vec<String> = vec![
+        sf!("len(\"\")"),
+        sf!("len(\"four\")"),
+        sf!("len(\"hello world\")"),
+        sf!("len([1, 2, 3])"),
+        sf!("len({1: 2})"),
+        sf!("first([1, 2, 3])"),
+        sf!("last([1, 2, 3])"),
+        sf!("len(rest([1, 2, 3]))"),
+        sf!("last(push([1, 2], 3))"),
+        sf!("first([])"),
+        sf!("rest([])"),
+        sf!("puts(\"hello\")"),
+        sf!("print(\"hello\", 1)"),
+    ];
+    let results: Vec<Option<i64>> = vec![
+        Some(0),
+        Some(4),
+        Some(11),
+        Some(3),
+        Some(1),
+        Some(1),
+        Some(3),
+        Some(2),
+        Some(3),
+        None,
+        None,
+        None,
+        None,
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let evaluated = test_eval(input.get(i).unwrap().clone());
+        test_opt_int_obj(evaluated, *r);
+    });
+
+    match test_eval(sf!("type([1, 2])")) {
+        Object::String { value } if value == "ARRAY" => {}
+        evaluated => panic!("type() did not return ARRAY, got={:#?}", evaluated),
+    }
+}
+
+#[test]
+fn test_builtin_errors() {
+    let input: Vec<String> = vec![
+        sf!("len(1)"),
+        sf!("len(\"one\", \"two\")"),
+        sf!("first(1)"),
+        sf!("push(1, 1)"),
+        sf!("rest()"),
+        sf!("5(1)"),
+    ];
+    let results: Vec<&str> = vec![
+        "argument to `len` not supported, got INTEGER",
+        "wrong number of arguments: want=1, got=2",
+        "argument to `first` must be ARRAY, got INTEGER",
+        "argument to `push` must be ARRAY, got INTEGER",
+        "wrong number of arguments: want=1, got=0",
+        "not a function: INTEGER",
+    ];
+
+    results.iter().enumerate().for_each(|(i, r)| {
+        let err = test_eval_error(input.get(i).unwrap().clone());
+        if *r != err.to_string() {
+            panic!("wrong error message. expected={}, got={}", *r, err);
+        }
+    });
+}
+
+fn eval_input(input: String) -> Result<Object, RuntimeError> {
     let l = Lexer::new(input.chars().collect());
     let mut p = Parser::new(l);
-    let program = p.parse_program();
-    let mut env = Environment::new();
+    let program = p.parse_program().unwrap();
+    let env = Rc::new(RefCell::new(Environment::new()));
 
     // println!("{:#?}", program.statements);
 
-    eval_statements(program.statements, &mut env, true)
+    eval_statements(program.statements, &env)
+}
+
+fn test_eval(input: String) -> Object {
+    match eval_input(input) {
+        Ok(evaluated) => evaluated,
+        Err(err) => panic!("unexpected runtime error: {}", err),
+    }
+}
+
+fn test_eval_error(input: String) -> RuntimeError {
+    match eval_input(input) {
+        Ok(evaluated) => panic!("No error returned. got={:#?}", evaluated),
+        Err(err) => err,
+    }
 }
 
 fn test_int_obj(eval: Object, r: i64) {
-    let eval_value;
-    match eval {
-        Object::Integer { value } => eval_value = value,
+    let eval_value = match eval {
+        Object::Integer { value } => value,
         _ => panic!("object is not integer, got={}", eval.get_type()),
-    }
+    };
 
     if eval_value != r {
         panic!("value is not {}, got={}", r, eval_value);
@@ -253,11 +988,10 @@ fn test_int_obj(eval: Object, r: i64) {
 }
 
 fn test_bool_obj(eval: Object, r: bool) {
-    let eval_value;
-    match eval {
-        Object::Boolean { value } => eval_value = value,
+    let eval_value = match eval {
+        Object::Boolean { value } => value,
         _ => panic!("object is not boolean, got={}", eval.get_type()),
-    }
+    };
 
     if eval_value != r {
         panic!("value is not {}, got={}", r, eval_value);
@@ -267,11 +1001,10 @@ fn test_bool_obj(eval: Object, r: bool) {
 fn test_opt_int_obj(eval: Object, r: Option<i64>) {
     match r {
         Some(r_val) => {
-            let eval_value;
-            match eval {
-                Object::Integer { value } => eval_value = value,
+            let eval_value = match eval {
+                Object::Integer { value } => value,
                 _ => panic!("object is not integer, got={}", eval.get_type()),
-            }
+            };
             if eval_value != r_val {
                 panic!("value is not {}, got={}", r_val, eval_value);
             }
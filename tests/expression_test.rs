@@ -1,5 +1,5 @@
 use d_lang::{
-    ast::{Node, Statement},
+    ast::{Expression, Node, Statement},
     lexer::Lexer,
     parser::Parser,
 };
@@ -12,8 +12,7 @@ fn test_identifier_expression() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() != 1 {
         panic!(
@@ -22,7 +21,7 @@ fn test_identifier_expression() {
         );
     }
 
-    let stmt = program.statements.get(0).unwrap();
+    let stmt = program.statements.first().unwrap();
 
     let expr = match stmt {
         Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -41,8 +40,7 @@ fn test_integer_literal_expression() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() != 1 {
         panic!(
@@ -51,7 +49,7 @@ fn test_integer_literal_expression() {
         );
     }
 
-    let stmt = program.statements.get(0).unwrap();
+    let stmt = program.statements.first().unwrap();
 
     let expr = match stmt {
         Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -63,6 +61,64 @@ fn test_integer_literal_expression() {
     }
 }
 
+#[test]
+fn test_number_literal_expressions() {
+    let tests = [
+        ("3.14", "3.14"),
+        ("1e-9", "1e-9"),
+        ("2.5E+3", "2500.0"),
+        ("9223372036854775807", "9223372036854775807"),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let l = Lexer::new(input.chars().collect());
+        let mut p = Parser::new(l);
+        let program = p.parse_program().unwrap();
+
+        let expr = match program.statements.first() {
+            Some(Statement::ExpressionStatement { expression, .. }) => expression.clone(),
+            stmt => panic!("Statement is not EXPRESSION, got={:#?}", stmt),
+        };
+
+        match expr {
+            Expression::FloatLiteral { .. } if input.len() < 10 => {}
+            Expression::IntegerLiteral { value, .. } if value == i64::MAX => {}
+            _ => panic!("wrong literal for {}, got={:#?}", input, expr),
+        }
+        if expr.to_string() != *expected {
+            panic!("expr is not {}, got={}", expected, expr.to_string());
+        }
+    });
+}
+
+#[test]
+fn test_radix_integer_literals() {
+    let tests = [
+        ("0xFF", 255),
+        ("0xdead_BEEF", 0xdead_beef),
+        ("0o755", 0o755),
+        ("0b1010", 10),
+        ("1_000_000", 1_000_000),
+        ("0x7FFF_FFFF_FFFF_FFFF", i64::MAX),
+        ("-9223372036854775808", i64::MIN),
+        ("-0x8000_0000_0000_0000", i64::MIN),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let l = Lexer::new(input.chars().collect());
+        let mut p = Parser::new(l);
+        let program = p.parse_program().unwrap();
+
+        match program.statements.first() {
+            Some(Statement::ExpressionStatement {
+                expression: Expression::IntegerLiteral { value, .. },
+                ..
+            }) if value == expected => {}
+            stmt => panic!("expected {} for {}, got={:#?}", expected, input, stmt),
+        }
+    });
+}
+
 #[test]
 fn test_prefix_expressions() {
     let input: Vec<String> = vec![sf!("!5;"), sf!("-14;")];
@@ -71,8 +127,7 @@ fn test_prefix_expressions() {
     results.iter().enumerate().for_each(|(idx, result)| {
         let l = Lexer::new(input.get(idx).unwrap().chars().collect());
         let mut p = Parser::new(l);
-        let program = p.parse_program();
-        p.check_parse_errors();
+        let program = p.parse_program().unwrap();
 
         if program.statements.len() != 1 {
             panic!(
@@ -81,7 +136,7 @@ fn test_prefix_expressions() {
             );
         }
 
-        let stmt = program.statements.get(0).unwrap();
+        let stmt = program.statements.first().unwrap();
 
         let expr = match stmt {
             Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -121,8 +176,7 @@ fn test_infix_expressions() {
     results.iter().enumerate().for_each(|(idx, result)| {
         let l = Lexer::new(input.get(idx).unwrap().chars().collect());
         let mut p = Parser::new(l);
-        let program = p.parse_program();
-        p.check_parse_errors();
+        let program = p.parse_program().unwrap();
 
         if program.statements.len() != 1 {
             panic!(
@@ -131,7 +185,7 @@ fn test_infix_expressions() {
             );
         }
 
-        let stmt = program.statements.get(0).unwrap();
+        let stmt = program.statements.first().unwrap();
 
         let expr = match stmt {
             Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -162,8 +216,7 @@ fn test_if_expression() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() != 1 {
         panic!(
@@ -172,7 +225,7 @@ fn test_if_expression() {
         );
     }
 
-    let stmt = program.statements.get(0).unwrap();
+    let stmt = program.statements.first().unwrap();
 
     let expr = match stmt {
         Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -208,8 +261,7 @@ fn test_func_expression() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() != 1 {
         panic!(
@@ -218,7 +270,7 @@ fn test_func_expression() {
         );
     }
 
-    let stmt = program.statements.get(0).unwrap();
+    let stmt = program.statements.first().unwrap();
 
     let expr = match stmt {
         Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -234,6 +286,31 @@ fn test_func_expression() {
     }
 }
 
+#[test]
+fn test_func_default_parameters() {
+    let input: Vec<char> = "fn(x, y = 2, z = x * y) { x + y + z };".chars().collect();
+
+    let result = "fn(x, y = 2, z = (x * y)) { ((x + y) + z) }";
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+
+    let program = p.parse_program().unwrap();
+
+    let expr = match program.statements.first() {
+        Some(Statement::ExpressionStatement { expression, .. }) => expression.clone(),
+        stmt => panic!("Statement is not EXPRESSION. got={:#?}", stmt),
+    };
+
+    if expr.to_string() != result {
+        panic!(
+            "expr is not correct expected={}, got={}",
+            result,
+            expr.to_string()
+        );
+    }
+}
+
 #[test]
 fn test_call_expression() {
     let input: Vec<char> = "add(1, 2 * 3, 4 + 5);".chars().collect();
@@ -243,8 +320,7 @@ fn test_call_expression() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() != 1 {
         panic!(
@@ -253,7 +329,7 @@ fn test_call_expression() {
         );
     }
 
-    let stmt = program.statements.get(0).unwrap();
+    let stmt = program.statements.first().unwrap();
 
     let expr = match stmt {
         Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -268,3 +344,110 @@ fn test_call_expression() {
         );
     }
 }
+
+#[test]
+fn test_string_literal_expression() {
+    let input: Vec<char> = "\"hello world\";".chars().collect();
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+
+    let program = p.parse_program().unwrap();
+
+    if program.statements.len() != 1 {
+        panic!(
+            "program.statements does not have enough statements. got {}",
+            program.statements.len()
+        );
+    }
+
+    let stmt = program.statements.first().unwrap();
+
+    let value = match stmt {
+        Statement::ExpressionStatement {
+            expression: Expression::StringLiteral { value, .. },
+            ..
+        } => value.clone(),
+        _ => panic!("Statement is not STRING expression, got={:#?}", stmt),
+    };
+
+    if value != "hello world" {
+        panic!("value is not 'hello world', got={}", value);
+    }
+}
+
+#[test]
+fn test_array_literal_expression() {
+    let input: Vec<String> = vec![sf!("[1, 2 * 2, 3 + 3]"), sf!("[]"), sf!("myArray[1 + 1]")];
+    let results: Vec<String> = vec![
+        sf!("[1, (2 * 2), (3 + 3)]"),
+        sf!("[]"),
+        sf!("(myArray[(1 + 1)])"),
+    ];
+
+    results.iter().enumerate().for_each(|(idx, result)| {
+        let l = Lexer::new(input.get(idx).unwrap().chars().collect());
+        let mut p = Parser::new(l);
+        let program = p.parse_program().unwrap();
+
+        if program.statements.len() != 1 {
+            panic!(
+                "program.statements does not have enough statements. got {}",
+                program.statements.len()
+            );
+        }
+
+        let stmt = program.statements.first().unwrap();
+
+        let expr = match stmt {
+            Statement::ExpressionStatement { expression, .. } => expression.clone(),
+            _ => panic!("Statement is not EXPRESSION"),
+        };
+
+        if expr.to_string() != *result {
+            panic!("expr is not {}, got={}", result, expr.to_string());
+        }
+    })
+}
+
+#[test]
+fn test_hash_literal_expression() {
+    let input: Vec<String> = vec![
+        sf!("{\"one\": 1, \"two\": 2, \"three\": 3}"),
+        sf!("{}"),
+        sf!("{\"one\": 0 + 1, true: 10 - 8, 3: 15 / 5}"),
+    ];
+    let results: Vec<String> = vec![
+        sf!("{one: 1, two: 2, three: 3}"),
+        sf!("{}"),
+        sf!("{one: (0 + 1), true: (10 - 8), 3: (15 / 5)}"),
+    ];
+
+    results.iter().enumerate().for_each(|(idx, result)| {
+        let l = Lexer::new(input.get(idx).unwrap().chars().collect());
+        let mut p = Parser::new(l);
+        let program = p.parse_program().unwrap();
+
+        if program.statements.len() != 1 {
+            panic!(
+                "program.statements does not have enough statements. got {}",
+                program.statements.len()
+            );
+        }
+
+        let stmt = program.statements.first().unwrap();
+
+        let expr = match stmt {
+            Statement::ExpressionStatement { expression, .. } => expression.clone(),
+            _ => panic!("Statement is not EXPRESSION"),
+        };
+
+        if !matches!(expr, Expression::HashLiteral { .. }) {
+            panic!("expr is not HashLiteral, got={:#?}", expr);
+        }
+
+        if expr.to_string() != *result {
+            panic!("expr is not {}, got={}", result, expr.to_string());
+        }
+    })
+}
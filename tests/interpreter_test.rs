@@ -0,0 +1,140 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use d_lang::{
+    evaluator::{max_call_depth, RuntimeError, DEFAULT_MAX_CALL_DEPTH},
+    interpreter::{DLangError, Interpreter},
+    object::Object,
+    token::Span,
+};
+use macros::sf;
+
+#[test]
+fn test_eval_str_keeps_globals() {
+    let mut interp = Interpreter::new();
+
+    interp
+        .eval_str("let add = fn(a, b) { a + b };")
+        .expect("failed to define add");
+    let evaluated = interp.eval_str("add(2, 3)").expect("failed to call add");
+
+    match evaluated {
+        Object::Integer { value: 5 } => {}
+        _ => panic!("object is not 5, got={:#?}", evaluated),
+    }
+}
+
+#[test]
+fn test_host_bindings() {
+    let mut interp = Interpreter::new();
+
+    interp.set_global("limit", Object::Integer { value: 10 });
+    interp.register_function("double", |args| match args.first() {
+        Some(Object::Integer { value }) => Ok(Object::Integer { value: value * 2 }),
+        _ => Err(RuntimeError::InvalidArgument {
+            message: sf!("double expects an INTEGER"),
+            span: Span::default(),
+        }),
+    });
+
+    interp
+        .eval_str("let result = double(limit) + 1;")
+        .expect("failed to evaluate script");
+
+    match interp.get_global("result") {
+        Some(Object::Integer { value: 21 }) => {}
+        result => panic!("result is not 21, got={:#?}", result),
+    }
+
+    if interp.get_global("missing").is_some() {
+        panic!("unbound global was found");
+    }
+}
+
+#[test]
+fn test_capturing_host_function() {
+    let mut interp = Interpreter::new();
+    let calls = Rc::new(Cell::new(0));
+
+    let counter = Rc::clone(&calls);
+    interp.register_function("tick", move |_| {
+        counter.set(counter.get() + 1);
+        Ok(Object::Integer {
+            value: counter.get(),
+        })
+    });
+
+    match interp.eval_str("tick(); tick(); tick()") {
+        Ok(Object::Integer { value: 3 }) => {}
+        result => panic!("tick() did not return 3, got={:#?}", result),
+    }
+    if calls.get() != 3 {
+        panic!("tick was called {} times, want 3", calls.get());
+    }
+}
+
+#[test]
+fn test_set_output() {
+    let mut interp = Interpreter::new();
+    let output = Rc::new(RefCell::new(Vec::<u8>::new()));
+    interp.set_output(output.clone());
+
+    interp
+        .eval_str(r#"puts("a", 1); print("b", [2]); puts()"#)
+        .expect("failed to evaluate script");
+
+    let output = String::from_utf8(output.borrow().clone()).unwrap();
+    if output != "a\n1\nb [2]" {
+        panic!("output is not {:?}, got={:?}", "a\n1\nb [2]", output);
+    }
+}
+
+#[test]
+fn test_eval_str_errors() {
+    let mut interp = Interpreter::new();
+
+    match interp.eval_str("5 + true;") {
+        Err(DLangError::Runtime(RuntimeError::TypeMismatch {
+            left,
+            operator,
+            right,
+            ..
+        })) => {
+            if (left.as_str(), operator.as_str(), right.as_str()) != ("INTEGER", "+", "BOOLEAN") {
+                panic!("wrong type mismatch. got={} {} {}", left, operator, right);
+            }
+        }
+        result => panic!("expected runtime error, got={:#?}", result),
+    }
+
+    match interp.eval_str("let = 5;") {
+        Err(DLangError::Parse(errors)) => {
+            if errors.is_empty() {
+                panic!("parse error list is empty");
+            }
+        }
+        result => panic!("expected parse error, got={:#?}", result),
+    }
+}
+
+#[test]
+fn test_max_call_depth_is_per_interpreter() {
+    let script = "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(20)";
+    let mut limited = Interpreter::new();
+    let mut unlimited = Interpreter::new();
+    limited.set_max_call_depth(10);
+
+    match limited.eval_str(script) {
+        Err(DLangError::Runtime(RuntimeError::StackOverflow { limit: 10, .. })) => {}
+        result => panic!("limited interpreter did not overflow, got={:#?}", result),
+    }
+    match unlimited.eval_str(script) {
+        Ok(Object::Integer { value: 20 }) => {}
+        result => panic!("limit leaked into another interpreter, got={:#?}", result),
+    }
+    if max_call_depth() != DEFAULT_MAX_CALL_DEPTH {
+        panic!("limit leaked into the thread, got={}", max_call_depth());
+    }
+}
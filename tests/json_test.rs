@@ -0,0 +1,169 @@
+use d_lang::{
+    ast::{Expression, Node, Statement},
+    json::{from_json, to_json, FORMAT_VERSION},
+    lexer::Lexer,
+    parser::Parser,
+    token::TokenType,
+};
+
+#[test]
+fn test_round_trip_sample() {
+    let input = include_str!("../sample.dl");
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+
+    let encoded = to_json(&program);
+    let decoded = match from_json(&encoded) {
+        Ok(decoded) => decoded,
+        Err(err) => panic!("failed to decode sample.dl: {}", err),
+    };
+
+    if decoded.statements.len() != program.statements.len() {
+        panic!(
+            "wrong number of statements. expected={}, got={}",
+            program.statements.len(),
+            decoded.statements.len()
+        );
+    }
+
+    program
+        .statements
+        .iter()
+        .zip(decoded.statements.iter())
+        .for_each(|(expected, got)| {
+            if expected.to_string() != got.to_string() {
+                panic!(
+                    "statement changed. expected={}, got={}",
+                    expected.to_string(),
+                    got.to_string()
+                );
+            }
+        });
+
+    let reencoded = to_json(&decoded);
+    if reencoded != encoded {
+        panic!("re-encoding the decoded program changed the json");
+    }
+}
+
+#[test]
+fn test_decode_keeps_tokens_and_spans() {
+    let input = "let x = 5;";
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = from_json(&to_json(&p.parse_program().unwrap())).unwrap();
+
+    match program.statements.first() {
+        Some(Statement::LetStatement {
+            value: Expression::IntegerLiteral { token, value: 5 },
+            ..
+        }) => {
+            if token.token_type != TokenType::INT || token.literal != "5" {
+                panic!("wrong token. got={:#?}", token);
+            }
+            if token.span.line != 1 || token.span.column != 9 || token.span.len != 1 {
+                panic!("wrong span. got={:#?}", token.span);
+            }
+        }
+        stmt => panic!("statement is not `let x = 5`. got={:#?}", stmt),
+    }
+}
+
+#[test]
+fn test_statement_tokens_have_spans() {
+    let input = "let x = 5;\n  x + 1;\nfn() { return x; };";
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let encoded: serde_json::Value =
+        serde_json::from_str(&to_json(&p.parse_program().unwrap())).unwrap();
+
+    let statements = &encoded["statements"];
+    let tests = [
+        (&statements[0]["token"], "LET", "let", 1, 1),
+        (&statements[1]["token"], "IDENT", "x", 2, 3),
+        (
+            &statements[2]["expression"]["body"]["statements"][0]["token"],
+            "RETURN",
+            "return",
+            3,
+            8,
+        ),
+    ];
+
+    tests
+        .iter()
+        .for_each(|(token, token_type, literal, line, column)| {
+            if token["type"] != *token_type
+                || token["literal"] != *literal
+                || token["span"]["line"] != *line
+                || token["span"]["column"] != *column
+            {
+                panic!(
+                    "wrong token. expected {} `{}` at {}:{}, got={}",
+                    token_type, literal, line, column, token
+                );
+            }
+        });
+}
+
+#[test]
+fn test_rejects_other_versions() {
+    let input = format!(
+        "{{\"version\": {}, \"statements\": []}}",
+        FORMAT_VERSION + 1
+    );
+
+    match from_json(&input) {
+        Err(err) => {
+            let expected = format!(
+                "invalid AST json: unsupported version {}, expected {}",
+                FORMAT_VERSION + 1,
+                FORMAT_VERSION
+            );
+            if err.to_string() != expected {
+                panic!("wrong error message. got={}", err);
+            }
+        }
+        Ok(_) => panic!("expected an error for an unknown version"),
+    }
+}
+
+#[test]
+fn test_rejects_trees_the_parser_cannot_build() {
+    let encode = |input: &str| {
+        let l = Lexer::new(input.chars().collect());
+        let mut p = Parser::new(l);
+        to_json(&p.parse_program().unwrap())
+    };
+    let tests = [
+        (
+            encode("-1;").replace("\"operator\": \"-\"", "\"operator\": \"~\""),
+            "unknown prefix operator `~`",
+        ),
+        (
+            encode("1 + 2;").replace("\"operator\": \"+\"", "\"operator\": \"^\""),
+            "unknown infix operator `^`",
+        ),
+        (
+            encode("x += 1;").replace("\"operator\": \"+=\"", "\"operator\": \"+\""),
+            "unknown assign operator `+`",
+        ),
+        (
+            encode("x = 1;").replace("\"kind\": \"Identifier\"", "\"kind\": \"StringLiteral\""),
+            "assignment target is not an identifier",
+        ),
+        (
+            encode("1;").replace("\"kind\": \"IntegerLiteral\"", "\"kind\": \"NoExpression\""),
+            "unknown expression kind `NoExpression`",
+        ),
+    ];
+
+    tests
+        .iter()
+        .for_each(|(input, message)| match from_json(input) {
+            Err(err) if err.message == *message => {}
+            Err(err) => panic!("expected error {:?}. got={:?}", message, err.message),
+            Ok(_) => panic!("expected error {:?}, decoding succeeded", message),
+        });
+}
@@ -1,5 +1,6 @@
 use d_lang::{
     lexer::Lexer,
+    parser::ParseError,
     token::{Token, TokenType::*},
 };
 use macros::sf;
@@ -12,7 +13,7 @@ fn test_next_token() {
     x + y;
     };
     let result = add(five, ten);
-    !-/*5;
+    !-/ *5;
     5 < 10 > 5;
 
     if (5 < 10) {
@@ -22,7 +23,11 @@ fn test_next_token() {
     }
     
     10 == 10;
-    10 != 9;"
+    10 != 9;
+    while (true) { break; continue; }
+    for i in 0..10 {}
+    x += 1 -= 2 *= 3 /= 4;
+    1 <= 2 >= 3 % 4;"
         .chars()
         .collect();
 
@@ -31,24 +36,128 @@ fn test_next_token() {
         "x", ",", "y", ")", "{", "x", "+", "y", ";", "}", ";", "let", "result", "=", "add", "(",
         "five", ",", "ten", ")", ";", "!", "-", "/", "*", "5", ";", "5", "<", "10", ">", "5", ";",
         "if", "(", "5", "<", "10", ")", "{", "return", "true", ";", "}", "else", "{", "return",
-        "false", ";", "}", "10", "==", "10", ";", "10", "!=", "9", ";", "\0",
+        "false", ";", "}", "10", "==", "10", ";", "10", "!=", "9", ";", "while", "(", "true", ")",
+        "{", "break", ";", "continue", ";", "}", "for", "i", "in", "0", "..", "10", "{", "}", "x",
+        "+=", "1", "-=", "2", "*=", "3", "/=", "4", ";", "1", "<=", "2", ">=", "3", "%", "4", ";",
+        "\0",
     ];
     let token_types = vec![
-        LET, IDENT, ASSIGN, INT, SEMICOLON, LET, IDENT, ASSIGN, INT, SEMICOLON, LET, IDENT, ASSIGN,
-        FUNCTION, LPAREN, IDENT, COMMA, IDENT, RPAREN, LBRACE, IDENT, PLUS, IDENT, SEMICOLON,
-        RBRACE, SEMICOLON, LET, IDENT, ASSIGN, IDENT, LPAREN, IDENT, COMMA, IDENT, RPAREN,
-        SEMICOLON, BANG, MINUS, SLASH, ASTERISK, INT, SEMICOLON, INT, LT, INT, GT, INT, SEMICOLON,
-        IF, LPAREN, INT, LT, INT, RPAREN, LBRACE, RETURN, TRUE, SEMICOLON, RBRACE, ELSE, LBRACE,
-        RETURN, FALSE, SEMICOLON, RBRACE, INT, EQ, INT, SEMICOLON, INT, NOTEQ, INT, SEMICOLON, EOF,
+        LET,
+        IDENT,
+        ASSIGN,
+        INT,
+        SEMICOLON,
+        LET,
+        IDENT,
+        ASSIGN,
+        INT,
+        SEMICOLON,
+        LET,
+        IDENT,
+        ASSIGN,
+        FUNCTION,
+        LPAREN,
+        IDENT,
+        COMMA,
+        IDENT,
+        RPAREN,
+        LBRACE,
+        IDENT,
+        PLUS,
+        IDENT,
+        SEMICOLON,
+        RBRACE,
+        SEMICOLON,
+        LET,
+        IDENT,
+        ASSIGN,
+        IDENT,
+        LPAREN,
+        IDENT,
+        COMMA,
+        IDENT,
+        RPAREN,
+        SEMICOLON,
+        BANG,
+        MINUS,
+        SLASH,
+        ASTERISK,
+        INT,
+        SEMICOLON,
+        INT,
+        LT,
+        INT,
+        GT,
+        INT,
+        SEMICOLON,
+        IF,
+        LPAREN,
+        INT,
+        LT,
+        INT,
+        RPAREN,
+        LBRACE,
+        RETURN,
+        TRUE,
+        SEMICOLON,
+        RBRACE,
+        ELSE,
+        LBRACE,
+        RETURN,
+        FALSE,
+        SEMICOLON,
+        RBRACE,
+        INT,
+        EQ,
+        INT,
+        SEMICOLON,
+        INT,
+        NOTEQ,
+        INT,
+        SEMICOLON,
+        WHILE,
+        LPAREN,
+        TRUE,
+        RPAREN,
+        LBRACE,
+        BREAK,
+        SEMICOLON,
+        CONTINUE,
+        SEMICOLON,
+        RBRACE,
+        FOR,
+        IDENT,
+        IN,
+        INT,
+        DOTDOT,
+        INT,
+        LBRACE,
+        RBRACE,
+        IDENT,
+        PLUSASSIGN,
+        INT,
+        MINUSASSIGN,
+        INT,
+        ASTERISKASSIGN,
+        INT,
+        SLASHASSIGN,
+        INT,
+        SEMICOLON,
+        INT,
+        LTEQ,
+        INT,
+        GTEQ,
+        INT,
+        PERCENT,
+        INT,
+        SEMICOLON,
+        EOF,
     ];
 
     let test_tokens: Vec<Token> = token_types
         .iter()
         .zip(literals)
-        .map(|(token_type, literal)| Token {
-            token_type: *token_type,
-            literal: sf!(literal),
-        })
+        .map(|(token_type, literal)| Token::new(*token_type, sf!(literal)))
         .collect();
 
     let mut l = Lexer::new(input);
@@ -66,3 +175,238 @@ fn test_next_token() {
         }
     });
 }
+
+#[test]
+fn test_string_tokens() {
+    let input = r#""foobar" "foo bar" "line\nbreak" "tab\there" "say \"hi\"" "back\\slash" "open"#
+        .chars()
+        .collect();
+
+    let literals = vec![
+        "foobar",
+        "foo bar",
+        "line\nbreak",
+        "tab\there",
+        "say \"hi\"",
+        "back\\slash",
+        "\"",
+        "\0",
+    ];
+    let token_types = [STRING, STRING, STRING, STRING, STRING, STRING, ILLEGAL, EOF];
+
+    let mut l = Lexer::new(input);
+
+    token_types
+        .iter()
+        .zip(literals)
+        .for_each(|(token_type, literal)| {
+            let input_token = l.next_token();
+            let test_token = Token::new(*token_type, sf!(literal));
+            if input_token != test_token {
+                panic!(
+                    "Error in token: expected: ({:#?}, {:#?}), got ({:#?}, {:#?})",
+                    test_token.token_type,
+                    test_token.literal,
+                    input_token.token_type,
+                    input_token.literal
+                );
+            }
+        });
+}
+
+#[test]
+fn test_identifiers() {
+    let input = "let x1 = größe + 変数 - _tmp2;
+    fn_ lets iff return2 café_au_lait Δx
+    ifx if 9lives"
+        .chars()
+        .collect();
+
+    let expected = [
+        (LET, "let"),
+        (IDENT, "x1"),
+        (ASSIGN, "="),
+        (IDENT, "größe"),
+        (PLUS, "+"),
+        (IDENT, "変数"),
+        (MINUS, "-"),
+        (IDENT, "_tmp2"),
+        (SEMICOLON, ";"),
+        (IDENT, "fn_"),
+        (IDENT, "lets"),
+        (IDENT, "iff"),
+        (IDENT, "return2"),
+        (IDENT, "café_au_lait"),
+        (IDENT, "Δx"),
+        (IDENT, "ifx"),
+        (IF, "if"),
+        (INT, "9"),
+        (IDENT, "lives"),
+        (EOF, "\0"),
+    ];
+
+    let mut l = Lexer::new(input);
+
+    expected.iter().for_each(|(token_type, literal)| {
+        let input_token = l.next_token();
+        let test_token = Token::new(*token_type, sf!(*literal));
+        if input_token != test_token {
+            panic!(
+                "Error in token: expected: ({:#?}, {:#?}), got ({:#?}, {:#?})",
+                test_token.token_type,
+                test_token.literal,
+                input_token.token_type,
+                input_token.literal
+            );
+        }
+    });
+
+    let mut l = Lexer::new("größe == 変数".chars().collect());
+    let spans: Vec<(usize, usize)> = (0..3)
+        .map(|_| l.next_token().span)
+        .map(|span| (span.column, span.len))
+        .collect();
+    if spans != [(1, 5), (7, 2), (10, 2)] {
+        panic!("wrong spans for unicode identifiers. got={:?}", spans);
+    }
+}
+
+#[test]
+fn test_number_tokens() {
+    let input = "3.14 1e-9 2.5E+3 10 0..10 4.5..6 1.x 1e 7e+ 42.5e3
+    0xFF 0o7_55 0b1010 1_000_000 1_0.5 0b102+1 0x 0xG"
+        .chars()
+        .collect();
+
+    let expected = [
+        (FLOAT, "3.14"),
+        (FLOAT, "1e-9"),
+        (FLOAT, "2.5E+3"),
+        (INT, "10"),
+        (INT, "0"),
+        (DOTDOT, ".."),
+        (INT, "10"),
+        (FLOAT, "4.5"),
+        (DOTDOT, ".."),
+        (INT, "6"),
+        (INT, "1"),
+        (ILLEGAL, "."),
+        (IDENT, "x"),
+        (INT, "1"),
+        (IDENT, "e"),
+        (INT, "7"),
+        (IDENT, "e"),
+        (PLUS, "+"),
+        (FLOAT, "42.5e3"),
+        (INT, "0xFF"),
+        (INT, "0o7_55"),
+        (INT, "0b1010"),
+        (INT, "1_000_000"),
+        (FLOAT, "1_0.5"),
+        (INT, "0b102"),
+        (PLUS, "+"),
+        (INT, "1"),
+        (INT, "0x"),
+        (INT, "0xG"),
+        (EOF, "\0"),
+    ];
+
+    let mut l = Lexer::new(input);
+
+    expected.iter().for_each(|(token_type, literal)| {
+        let input_token = l.next_token();
+        let test_token = Token::new(*token_type, sf!(*literal));
+        if input_token != test_token {
+            panic!(
+                "Error in token: expected: ({:#?}, {:#?}), got ({:#?}, {:#?})",
+                test_token.token_type,
+                test_token.literal,
+                input_token.token_type,
+                input_token.literal
+            );
+        }
+    });
+}
+
+#[test]
+fn test_comments() {
+    let input = "// a line comment
+    let x = 1; // trailing
+    /// doc comments are line comments too
+    /* block /* nested */ still comment */ x / 2 /**/
+    \"// in a string\" /* open"
+        .chars()
+        .collect();
+
+    let expected = [
+        (LET, "let", 2, 5),
+        (IDENT, "x", 2, 9),
+        (ASSIGN, "=", 2, 11),
+        (INT, "1", 2, 13),
+        (SEMICOLON, ";", 2, 14),
+        (IDENT, "x", 4, 44),
+        (SLASH, "/", 4, 46),
+        (INT, "2", 4, 48),
+        (STRING, "// in a string", 5, 5),
+        (EOF, "\0", 5, 29),
+    ];
+
+    let mut l = Lexer::new(input);
+
+    expected
+        .iter()
+        .for_each(|(token_type, literal, line, column)| {
+            let token = l.next_token();
+            if token.token_type != *token_type
+                || token.literal != *literal
+                || token.span.line != *line
+                || token.span.column != *column
+            {
+                panic!(
+                    "wrong token. expected={:?} {:?} at {}:{}, got={:?} {:?} at {}:{}",
+                    token_type,
+                    literal,
+                    line,
+                    column,
+                    token.token_type,
+                    token.literal,
+                    token.span.line,
+                    token.span.column
+                );
+            }
+        });
+
+    match l.take_errors().as_slice() {
+        [ParseError::UnterminatedComment { span }] if span.line == 5 && span.column == 22 => {}
+        errors => panic!("expected one UnterminatedComment, got={:#?}", errors),
+    }
+}
+
+#[test]
+fn test_token_positions() {
+    let input = "let x = 5;\n  x == \"hi\";".chars().collect();
+
+    let spans = [
+        (1, 1, 3),
+        (1, 5, 1),
+        (1, 7, 1),
+        (1, 9, 1),
+        (1, 10, 1),
+        (2, 3, 1),
+        (2, 5, 2),
+        (2, 8, 4),
+        (2, 12, 1),
+    ];
+
+    let mut l = Lexer::new(input);
+
+    spans.iter().for_each(|(line, column, len)| {
+        let token = l.next_token();
+        if token.span.line != *line || token.span.column != *column || token.span.len != *len {
+            panic!(
+                "wrong span for {:#?}. expected={}:{} len {}, got={:#?}",
+                token.literal, line, column, len, token.span
+            );
+        }
+    });
+}
@@ -17,6 +17,14 @@ fn test_operator_precedence() {
         sf!("2 / (5 + 5)"),
         sf!("-(5 + 5)"),
         sf!("!(true == true)"),
+        sf!("a * [1, 2, 3, 4][b * c] * d"),
+        sf!("add(a * b[2], b[1], 2 * [1, 2][1])"),
+        sf!("x = y = 1 + 2"),
+        sf!("x += a == b"),
+        sf!("r = 0..n * 2"),
+        sf!("a + b % c * d"),
+        sf!("a <= b == c >= d"),
+        sf!("x % 2 == 0"),
     ];
 
     let results: Vec<String> = vec![
@@ -29,13 +37,20 @@ fn test_operator_precedence() {
         sf!("(2 / (5 + 5))"),
         sf!("(-(5 + 5))"),
         sf!("(!(true == true))"),
+        sf!("((a * ([1, 2, 3, 4][(b * c)])) * d)"),
+        sf!("add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))"),
+        sf!("(x = (y = (1 + 2)))"),
+        sf!("(x += (a == b))"),
+        sf!("(r = (0 .. (n * 2)))"),
+        sf!("(a + ((b % c) * d))"),
+        sf!("((a <= b) == (c >= d))"),
+        sf!("((x % 2) == 0)"),
     ];
 
     results.iter().enumerate().for_each(|(idx, result)| {
         let l = Lexer::new(input.get(idx).unwrap().chars().collect());
         let mut p = Parser::new(l);
-        let program = p.parse_program();
-        p.check_parse_errors();
+        let program = p.parse_program().unwrap();
 
         if program.statements.len() != 1 {
             panic!(
@@ -44,7 +59,7 @@ fn test_operator_precedence() {
             );
         }
 
-        let stmt = program.statements.get(0).unwrap();
+        let stmt = program.statements.first().unwrap();
 
         let expr = match stmt {
             Statement::ExpressionStatement { expression, .. } => expression.clone(),
@@ -0,0 +1,302 @@
+use d_lang::{
+    lexer::Lexer,
+    parser::{ParseError, Parser},
+    token::TokenType,
+};
+use macros::sf;
+
+fn parse_errors(input: &str) -> Vec<ParseError> {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+
+    match p.parse_program() {
+        Ok(_) => panic!("expected parse errors for {:#?}", input),
+        Err(errors) => errors,
+    }
+}
+
+#[test]
+fn test_unexpected_token() {
+    let errors = parse_errors("let x 5;");
+
+    match errors.first() {
+        Some(ParseError::UnexpectedToken {
+            expected: TokenType::ASSIGN,
+            got: TokenType::INT,
+            span,
+            ..
+        }) => {
+            if span.line != 1 || span.column != 7 {
+                panic!("wrong span. got={:#?}", span);
+            }
+        }
+        err => panic!("expected UnexpectedToken, got={:#?}", err),
+    }
+
+    let message = errors.first().unwrap().to_string();
+    if message != "expected token ASSIGN, got INT `5`" {
+        panic!("wrong error message. got={}", message);
+    }
+}
+
+#[test]
+fn test_no_prefix_parse_fn() {
+    let errors = parse_errors("let x = ;");
+
+    match errors.first() {
+        Some(ParseError::NoPrefixParseFn {
+            token_type: TokenType::SEMICOLON,
+            ..
+        }) => {}
+        err => panic!("expected NoPrefixParseFn, got={:#?}", err),
+    }
+}
+
+#[test]
+fn test_unterminated_block() {
+    let errors = parse_errors("let f = fn(x) {\n  x + 1;\n");
+
+    match errors.first() {
+        Some(ParseError::UnterminatedBlock { span }) => {
+            if span.line != 1 || span.column != 15 {
+                panic!("wrong span. got={:#?}", span);
+            }
+        }
+        err => panic!("expected UnterminatedBlock, got={:#?}", err),
+    }
+}
+
+#[test]
+fn test_unterminated_comment() {
+    let errors = parse_errors("let x = 1; /* never /* closed */");
+
+    match errors.first() {
+        Some(err @ ParseError::UnterminatedComment { span }) => {
+            if span.line != 1 || span.column != 12 {
+                panic!("wrong span. got={:#?}", span);
+            }
+            if err.to_string() != "unterminated block comment, expected `*/` before end of input" {
+                panic!("wrong message. got={}", err);
+            }
+        }
+        err => panic!("expected UnterminatedComment, got={:#?}", err),
+    }
+
+    match parse_errors("let x = 1 /* trailing").as_slice() {
+        [ParseError::UnterminatedComment { span }] if span.column == 11 => {}
+        errors => panic!("expected one UnterminatedComment, got={:#?}", errors),
+    }
+
+    let l = Lexer::new("let x = 1; // no /* block here".chars().collect());
+    if let Err(errors) = Parser::new(l).parse_program() {
+        panic!("line comment was not skipped, got={:#?}", errors);
+    }
+}
+
+#[test]
+fn test_number_out_of_range() {
+    let tests = [
+        ("9223372036854775808", "9223372036854775808", 1),
+        (
+            "let x = 1 + 99999999999999999999;",
+            "99999999999999999999",
+            13,
+        ),
+        ("2.0 * 1e400", "1e400", 7),
+        ("0x8000_0000_0000_0000", "0x8000_0000_0000_0000", 1),
+        ("-9223372036854775809", "9223372036854775809", 2),
+    ];
+
+    tests.iter().for_each(
+        |(input, literal, column)| match parse_errors(input).first() {
+            Some(err @ ParseError::NumberOutOfRange { span, .. }) if span.column == *column => {
+                let expected = format!("number `{}` is too large to represent", literal);
+                if err.to_string() != expected {
+                    panic!("wrong message. expected={}, got={}", expected, err);
+                }
+            }
+            err => panic!("expected NumberOutOfRange for {:?}, got={:#?}", input, err),
+        },
+    );
+}
+
+#[test]
+fn test_invalid_digits() {
+    let tests = [
+        ("0b102", '2', 2, 5),
+        ("let x = 0o78;", '8', 8, 12),
+        ("0xFG", 'G', 16, 4),
+        ("1 + 0b1_0_a", 'a', 2, 11),
+    ];
+
+    tests.iter().for_each(
+        |(input, digit, radix, column)| match parse_errors(input).first() {
+            Some(ParseError::InvalidDigit {
+                digit: got,
+                radix: got_radix,
+                span,
+            }) if got == digit && got_radix == radix && span.column == *column => {}
+            err => panic!("expected InvalidDigit for {:?}, got={:#?}", input, err),
+        },
+    );
+
+    let err = &parse_errors("0b102")[0];
+    if err.to_string() != "invalid digit `2` in a base 2 literal" {
+        panic!("wrong message. got={}", err);
+    }
+
+    match parse_errors("0x + 0b").as_slice() {
+        [ParseError::InvalidInteger { literal: a, .. }, ParseError::InvalidInteger { literal: b, .. }]
+            if a == "0x" && b == "0b" => {}
+        errors => panic!(
+            "expected InvalidInteger for empty literals, got={:#?}",
+            errors
+        ),
+    }
+}
+
+#[test]
+fn test_misplaced_separators() {
+    let tests = [
+        ("1_", 2),
+        ("let x = 1__0;", 10),
+        ("0x_FF", 3),
+        ("0xFF_", 5),
+        ("0b_", 3),
+        ("1_.5", 2),
+        ("1.5_e3", 4),
+    ];
+
+    tests
+        .iter()
+        .for_each(|(input, column)| match parse_errors(input).as_slice() {
+            [err @ ParseError::MisplacedSeparator { span }] if span.column == *column => {
+                if err.to_string() != "misplaced `_`, separators go between two digits" {
+                    panic!("wrong message. got={}", err);
+                }
+            }
+            errors => panic!(
+                "expected MisplacedSeparator for {:?}, got={:#?}",
+                input, errors
+            ),
+        });
+}
+
+#[test]
+fn test_required_after_default() {
+    let errors = parse_errors("fn(a = 1, b) { a + b }");
+
+    match errors.first() {
+        Some(ParseError::RequiredAfterDefault { name, span }) if name == "b" => {
+            if span.line != 1 || span.column != 11 {
+                panic!("wrong span. got={:#?}", span);
+            }
+        }
+        err => panic!("expected RequiredAfterDefault, got={:#?}", err),
+    }
+}
+
+#[test]
+fn test_loop_control_outside_loop() {
+    let tests = [
+        ("break;", "break", 1, 1),
+        ("if (true) { continue; }", "continue", 1, 13),
+        ("while (true) { let f = fn() { break; }; }", "break", 1, 31),
+    ];
+
+    tests.iter().for_each(
+        |(input, keyword, line, column)| match parse_errors(input).first() {
+            Some(ParseError::OutsideLoop { keyword: got, span })
+                if got == keyword && span.line == *line && span.column == *column => {}
+            err => panic!("expected OutsideLoop for {:?}, got={:#?}", input, err),
+        },
+    );
+}
+
+#[test]
+fn test_expected_statement_end() {
+    let tests = [
+        ("let x = 1 2;", TokenType::INT, 11),
+        ("return x y;", TokenType::IDENT, 10),
+    ];
+
+    tests.iter().for_each(
+        |(input, token_type, column)| match parse_errors(input).first() {
+            Some(ParseError::ExpectedStatementEnd { got, span, .. })
+                if got == token_type && span.line == 1 && span.column == *column => {}
+            err => panic!(
+                "expected ExpectedStatementEnd for {:?}, got={:#?}",
+                input, err
+            ),
+        },
+    );
+
+    let message = parse_errors("let x = 1 2;").first().unwrap().to_string();
+    if message != "expected `;` or end of statement, got INT `2`" {
+        panic!("wrong error message. got={}", message);
+    }
+}
+
+#[test]
+fn test_bad_parameter_list_reports_once() {
+    let errors = parse_errors("fn(a,) { a }");
+    if errors.len() != 1 {
+        panic!("expected 1 error, got={:#?}", errors);
+    }
+}
+
+#[test]
+fn test_for_needs_in() {
+    let errors = parse_errors("for x [1, 2] { x }");
+
+    match errors.first() {
+        Some(ParseError::UnexpectedToken {
+            expected: TokenType::IN,
+            got: TokenType::LBRACKET,
+            ..
+        }) => {}
+        err => panic!("expected UnexpectedToken, got={:#?}", err),
+    }
+}
+
+#[test]
+fn test_recovers_and_reports_all_errors() {
+    let input: Vec<String> = vec![
+        sf!("(1 + 2"),
+        sf!("if (x) { 1 } else"),
+        sf!("fn(1) { 1 }"),
+        sf!("[1, 2"),
+        sf!("{\"a\" 1}"),
+        sf!("\"open"),
+        sf!("99999999999999999999"),
+    ];
+
+    input.iter().for_each(|inp| {
+        parse_errors(inp);
+    });
+
+    let errors = parse_errors("let = 1; let y 2;");
+    if errors.len() != 2 {
+        panic!("expected 2 errors, got={:#?}", errors);
+    }
+}
+
+#[test]
+fn test_invalid_assignment_target() {
+    let tests = [
+        ("5 = 1", "5", 1),
+        ("f() += 1", "f()", 1),
+        ("x = a[0] = 2", "(a[0])", 5),
+    ];
+
+    tests.iter().for_each(
+        |(input, target, column)| match parse_errors(input).first() {
+            Some(ParseError::InvalidAssignmentTarget { target: got, span })
+                if got == target && span.column == *column => {}
+            err => panic!(
+                "expected InvalidAssignmentTarget for {:?}, got={:#?}",
+                input, err
+            ),
+        },
+    );
+}
@@ -0,0 +1,71 @@
+use d_lang::repl::{is_incomplete, Command};
+use macros::sf;
+
+#[test]
+fn test_is_incomplete() {
+    let tests: Vec<(&str, bool)> = vec![
+        ("", false),
+        ("let x = 5;", false),
+        ("let fact = fn(x) {", true),
+        (
+            "let fact = fn(x) {\n  if (x < 2) { 1 } else { x * fact(x - 1) }\n};",
+            false,
+        ),
+        ("puts(1,", true),
+        ("[1, 2", true),
+        ("{\"a\": ", true),
+        ("1 +", true),
+        ("let x =", true),
+        ("x ==", true),
+        ("total +=", true),
+        ("n %", true),
+        ("x <=", true),
+        ("let x = 1; /* note", true),
+        ("/* note", true),
+        ("let x = 1; /* note */", false),
+        ("puts(1, // more below", true),
+        ("!", true),
+        ("\"unterminated", true),
+        ("\"closed\"", false),
+        ("1 + 2 }", false),
+        ("let ;", false),
+    ];
+
+    tests.into_iter().for_each(|(input, expected)| {
+        if is_incomplete(input) != expected {
+            panic!(
+                "is_incomplete({:?}) should be {}, got {}",
+                input, expected, !expected
+            );
+        }
+    });
+}
+
+#[test]
+fn test_parse_command() {
+    let tests: Vec<(&str, Result<Command, &str>)> = vec![
+        (":help", Ok(Command::Help)),
+        (":env\n", Ok(Command::Env)),
+        (":type  1 + 2 ", Ok(Command::Type(sf!("1 + 2")))),
+        (":ast fn(x) { x }", Ok(Command::Ast(sf!("fn(x) { x }")))),
+        (":tokens let", Ok(Command::Tokens(sf!("let")))),
+        (":load sample.dl", Ok(Command::Load(sf!("sample.dl")))),
+        (":reset", Ok(Command::Reset)),
+        (":quit", Ok(Command::Quit)),
+        (":type", Err("`:type` needs an expression")),
+        (":load ", Err("`:load` needs a file")),
+        (":quit now", Err("`:quit` takes no argument")),
+        (":exit", Err("unknown command `:exit`, see :help")),
+    ];
+
+    tests.into_iter().for_each(|(line, expected)| {
+        let expected = expected.map_err(String::from);
+        let command = Command::parse(line);
+        if command != expected {
+            panic!(
+                "wrong command for {:?}. expected={:?}, got={:?}",
+                line, expected, command
+            );
+        }
+    });
+}
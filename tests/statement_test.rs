@@ -15,8 +15,7 @@ fn test_let_statements() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() > 3 {
         panic!(
@@ -25,19 +24,17 @@ fn test_let_statements() {
         );
     }
 
-    let tests = vec!["x", "y", "foobar"];
+    let tests = ["x", "y", "foobar"];
 
     tests.iter().enumerate().for_each(|(index, exp_ident)| {
-        if !test_let_statement(
+        test_let_statement(
             program
                 .statements
                 .get(index)
                 .expect("No statement found!")
                 .clone(),
             exp_ident.to_string(),
-        ) {
-            return;
-        }
+        );
     });
 }
 
@@ -58,6 +55,41 @@ fn test_let_statement(stmt: Statement, name: String) -> bool {
     true
 }
 
+#[test]
+fn test_statements_end_at_newline() {
+    let input: Vec<char> = "let x = 5
+    puts(x)
+    return x"
+        .chars()
+        .collect();
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+
+    let program = p.parse_program().unwrap();
+
+    let expected = ["let x = 5;", "puts(x)", "return \"x\";"];
+    if program.statements.len() != expected.len() {
+        panic!(
+            "program.statements does not contain 3 statements. got={:#?}",
+            program.statements
+        );
+    }
+    program
+        .statements
+        .iter()
+        .zip(expected)
+        .for_each(|(stmt, expected)| {
+            if stmt.to_string() != expected {
+                panic!(
+                    "wrong statement. expected={}, got={}",
+                    expected,
+                    stmt.to_string()
+                );
+            }
+        });
+}
+
 #[test]
 fn test_return_statements() {
     let input: Vec<char> = "return 5;
@@ -69,8 +101,7 @@ fn test_return_statements() {
     let l = Lexer::new(input);
     let mut p = Parser::new(l);
 
-    let program = p.parse_program();
-    p.check_parse_errors();
+    let program = p.parse_program().unwrap();
 
     if program.statements.len() > 3 {
         panic!(
@@ -85,3 +116,116 @@ fn test_return_statements() {
         }
     }
 }
+
+#[test]
+fn test_while_statements() {
+    let input: Vec<char> = "while (x < 10) { if (x == 5) { break; } continue; }
+    let f = fn() { while (true) { return 1; } };"
+        .chars()
+        .collect();
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+
+    let program = p.parse_program().unwrap();
+
+    if program.statements.len() != 2 {
+        panic!(
+            "program.statements does not contain 2 statements. got {}",
+            program.statements.len()
+        );
+    }
+
+    match &program.statements[0] {
+        Statement::WhileStatement {
+            condition, body, ..
+        } => {
+            if condition.to_string() != "(x < 10)" {
+                panic!("wrong condition, got={}", condition.to_string());
+            }
+            if body.to_string() != "{ if (x == 5) { break; }continue; }" {
+                panic!("wrong body, got={}", body.to_string());
+            }
+        }
+        stmt => panic!("Statement is not WHILE, got={:#?}", stmt),
+    }
+
+    let expected = "let f = fn() { while true { return \"1\"; } };";
+    if program.statements[1].to_string() != expected {
+        panic!(
+            "expected {}, got={}",
+            expected,
+            program.statements[1].to_string()
+        );
+    }
+}
+
+#[test]
+fn test_while_condition_and_trailing_semicolon() {
+    let tests = [
+        ("while x < 10 { x += 1 }", "while (x < 10) { (x += 1) }"),
+        ("while (false) {}; puts(1)", "while false {  }"),
+        ("while true { break; }; 1", "while true { break; }"),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let l = Lexer::new(input.chars().collect());
+        let mut p = Parser::new(l);
+        let program = match p.parse_program() {
+            Ok(program) => program,
+            Err(errors) => panic!("failed to parse {:?}: {:?}", input, errors),
+        };
+
+        let expected_len = if input.contains("};") { 2 } else { 1 };
+        if program.statements.len() != expected_len {
+            panic!(
+                "{:?} does not contain {} statements. got {}",
+                input,
+                expected_len,
+                program.statements.len()
+            );
+        }
+        match program.statements.first() {
+            Some(stmt @ Statement::WhileStatement { .. }) => {
+                if stmt.to_string() != *expected {
+                    panic!("expected {}, got={}", expected, stmt.to_string());
+                }
+            }
+            stmt => panic!("Statement is not WHILE, got={:#?}", stmt),
+        }
+    });
+}
+
+#[test]
+fn test_for_statements() {
+    let tests = [
+        ("for x in [1, 2] { x; }", "for x in [1, 2] { x }"),
+        (
+            "for i in 0..n + 1 { break; }",
+            "for i in (0 .. (n + 1)) { break; }",
+        ),
+        (
+            "for k in {\"a\": 1} { continue; }",
+            "for k in {a: 1} { continue; }",
+        ),
+        (
+            "for i in 0..3 { puts(i) }; i",
+            "for i in (0 .. 3) { puts(i) }",
+        ),
+    ];
+
+    tests.iter().for_each(|(input, expected)| {
+        let l = Lexer::new(input.chars().collect());
+        let mut p = Parser::new(l);
+        let program = p.parse_program().unwrap();
+
+        match program.statements.first() {
+            Some(stmt @ Statement::ForStatement { .. }) => {
+                if stmt.to_string() != *expected {
+                    panic!("expected {}, got={}", expected, stmt.to_string());
+                }
+            }
+            stmt => panic!("Statement is not FOR, got={:#?}", stmt),
+        }
+    });
+}
@@ -6,20 +6,17 @@ use macros::sf;
 
 #[test]
 fn test_next_token() {
-    let input = "=+(){},;".chars().collect();
+    let input = "=+(){}[],;".chars().collect();
 
-    let literals = vec!["=", "+", "(", ")", "{", "}", ",", ";", "\0"];
+    let literals = vec!["=", "+", "(", ")", "{", "}", "[", "]", ",", ";", "\0"];
     let token_types = vec![
-        ASSIGN, PLUS, LPAREN, RPAREN, LBRACE, RBRACE, COMMA, SEMICOLON, EOF,
+        ASSIGN, PLUS, LPAREN, RPAREN, LBRACE, RBRACE, LBRACKET, RBRACKET, COMMA, SEMICOLON, EOF,
     ];
 
     let test_tokens: Vec<Token> = token_types
         .iter()
         .zip(literals)
-        .map(|(token_type, literal)| Token {
-            token_type: *token_type,
-            literal: sf!(literal),
-        })
+        .map(|(token_type, literal)| Token::new(*token_type, sf!(literal)))
         .collect();
 
     let mut l = Lexer::new(input);
@@ -0,0 +1,356 @@
+use std::{cell::RefCell, rc::Rc};
+
+use d_lang::{
+    compiler::compile,
+    environment::Environment,
+    evaluator::{eval_statements, set_max_call_depth, RuntimeError, DEFAULT_MAX_CALL_DEPTH},
+    lexer::Lexer,
+    object::Object,
+    parser::Parser,
+    vm,
+};
+use macros::sf;
+
+#[test]
+fn test_expressions() {
+    let input: Vec<String> = vec![
+        sf!("(5 + 10 * 2 + 15 / 3) * 2 + -10"),
+        sf!("50 / 2 * 2 + 10"),
+        sf!("(1 < 2) == true"),
+        sf!("(1 > 2) == false"),
+        sf!("!!5"),
+        sf!("!true"),
+        sf!("if (false) { 10 }"),
+        sf!("if (1) { 10 }"),
+        sf!("if (1 > 2) { 10 } else { 20 }"),
+        sf!("if (1 > 2) { 10 } else if (2 > 1) { 30 } else { 20 }"),
+        sf!("return 10; 9;"),
+        sf!("9; return 2 * 5; 9;"),
+        sf!("if (10 > 1) {
+                if (10 > 1) {
+                    return 10;
+                }
+                return 1;
+            }"),
+        sf!("let a = 5; let b = a; let c = a + b + 5; c;"),
+        sf!("let a = 5;"),
+        sf!("\"Hello\" + \" \" + \"World!\""),
+        sf!("\"a\" != \"b\""),
+        sf!(""),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_functions() {
+    let input: Vec<String> = vec![
+        sf!("fn(x) { x + 2; };"),
+        sf!("fn(a, b = 10) { a + b }"),
+        sf!("let identity = fn(x) { return x; }; identity(5);"),
+        sf!("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));"),
+        sf!("fn(x) { x; }(5)"),
+        sf!("let double = fn(x) { x + x; };
+        let triple = fn(x, y) { x + y(x);};
+        triple(2, double)"),
+        sf!("let new_adder = fn(x) { fn(y) { x + y } };
+        let add_two = new_adder(2);
+        add_two(3);"),
+        sf!("let x = 10; let f = fn(x) { x * 2 }; f(3); x;"),
+        sf!("let fact = fn(x) {
+            if x == 1 {
+                return 1;
+            }
+            return x * fact(x - 1);
+        };
+        fact(10);"),
+        sf!("let a = 1;
+        let get_a = fn() { a };
+        let shadow = fn() { let a = 2; get_a() };
+        shadow();"),
+        sf!("let f = fn() { let inner = 5; inner }; f();"),
+        sf!("let f = fn() { }; f();"),
+        sf!("let add = fn(a, b = 10) { a + b }; add(1) + add(1, 2);"),
+        sf!("let f = fn(a, b = a * 2) { a + b }; f(3);"),
+        sf!("let n = 4; let f = fn(a = n) { a }; f();"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_collections_and_builtins() {
+    let input: Vec<String> = vec![
+        sf!("[1, 2 * 2, 3 + 3]"),
+        sf!("let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i]"),
+        sf!("[1, 2, 3][3]"),
+        sf!("[1, 2, 3][-1]"),
+        sf!("let two = \"two\";
+        {\"one\": 10 - 9, two: 1 + 1, \"thr\" + \"ee\": 6 / 2, 4: 4, true: 5, false: 6}"),
+        sf!("let key = \"foo\"; {\"foo\": 5}[key]"),
+        sf!("{}[\"foo\"]"),
+        sf!("len(\"hello world\")"),
+        sf!("len({1: 2})"),
+        sf!("len(rest([1, 2, 3]))"),
+        sf!("last(push([1, 2], 3))"),
+        sf!("first([])"),
+        sf!("type(fn(x) { x })"),
+        sf!("let len = fn(x) { 42 }; len([1]);"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_errors() {
+    let input: Vec<String> = vec![
+        sf!("5 + true; 5;"),
+        sf!("-true;"),
+        sf!("5; false + true; 5;"),
+        sf!("if (10 > 1) {
+                if (10 > 1) {
+                    return true + false;
+                }
+                return 1;
+            }"),
+        sf!("foobar;"),
+        sf!("\"Hello\" - \"World\""),
+        sf!("5[0]"),
+        sf!("{\"name\": \"d_lang\"}[fn(x) { x }];"),
+        sf!("{fn(x) { x }: 1};"),
+        sf!("let zero = 0; 10 / zero;"),
+        sf!("9223372036854775807 + 1"),
+        sf!("9223372036854775807 * 2"),
+        sf!("let m = -9223372036854775807 - 1; m / -1"),
+        sf!("let m = -9223372036854775807 - 1; m % -1"),
+        sf!("let m = -9223372036854775807 - 1; -m"),
+        sf!("let x = 9223372036854775807; x += 1"),
+        sf!("let f = fn() { let inner = 5; inner }; f(); inner;"),
+        sf!("let f = fn(x) {\n  x + true\n};\nf(1);"),
+        sf!("fn(a, b) { a + b }(1)"),
+        sf!("fn(a, b = 1) { a + b }(1, 2, 3)"),
+        sf!("fn(a, b = c) { a + b }(1)"),
+        sf!("len(1)"),
+        sf!("rest()"),
+        sf!("5(1)"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_tail_calls_and_call_depth() {
+    let input: Vec<String> = vec![
+        sf!(
+            "let countdown = fn(n) { if (n == 0) { return 0; } return countdown(n - 1); };
+        countdown(100000);"
+        ),
+        sf!(
+            "let sum = fn(n, acc) { if (n == 0) { acc } else { sum(n - 1, acc + n) } };
+        sum(10000, 0);"
+        ),
+        sf!("let f = fn(n) { 1 + f(n + 1) }; f(0);"),
+        sf!("let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(5000);"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+
+    set_max_call_depth(10);
+    assert_same("let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(10);");
+    assert_same("let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(9);");
+    set_max_call_depth(DEFAULT_MAX_CALL_DEPTH);
+}
+
+#[test]
+fn test_loops() {
+    let input: Vec<String> = vec![
+        sf!("let i = 0; while (i < 10) { let i = i + 1; } i"),
+        sf!("let i = 0; while (true) { if (i == 5) { break; } let i = i + 1; } i"),
+        sf!("let i = 0; let sum = 0;
+        while (i < 10) {
+            let i = i + 1;
+            if (i == 3) { continue; }
+            let sum = sum + i;
+        }
+        sum"),
+        sf!("let n = 0; let i = 0;
+        while (i < 3) {
+            let i = i + 1;
+            let j = 0;
+            while (true) {
+                if (j == 2) { break; }
+                let j = j + 1;
+                let n = n + 1;
+            }
+        }
+        n"),
+        sf!("let f = fn() {
+            let i = 0;
+            while (true) {
+                if (i == 4) { return i * 10; }
+                let i = i + 1;
+            }
+            99
+        };
+        f()"),
+        sf!("let f = fn(x) { while (x > 0) { return x; } 0 }; f(3) + f(0)"),
+        sf!("while (false) { 1 }"),
+        sf!("let i = 0; while (i < 3) { let i = i + 1; i }"),
+        sf!("while (1 + true) { 1 }"),
+        sf!("let f = fn(x) { if (x > 0) { x }; 0 }; f(5)"),
+        sf!("if (true) { 1 }; 2"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_loop_control_inside_expressions() {
+    let input: Vec<String> = vec![
+        sf!("let i = 0; while (if (i == 2) { break; } else { true }) { i += 1 } i"),
+        sf!("let i = 0; while (if (i < 3) { i += 1; continue; } else { false }) { i = 10 } i"),
+        sf!("let n = 0; let f = fn(a, b) { n += 1 };
+        for x in [1, 2, 3] { f(x, if (x == 2) { break; }) }
+        n"),
+        sf!("let y = 0; for x in [1, 2, 3] { y = if (x == 2) { break; } else { x } } y"),
+        sf!("let n = 0; for c in [true, false] { n = 1 + if (c) { continue; } else { n } } n"),
+        sf!("1 + if (true) { for x in [5, 6] { [x, x, if (true) { continue; }] }; 2 }"),
+        sf!("let i = 0; 1 + if (true) { while (i < 2) { i += 1; [i, if (true) { break; }] }; 2 }"),
+        sf!("let f = fn() { let a = [1, if (true) { return 7; }]; 0 }; f()"),
+        sf!("fn() { [1, if (true) { return 7; }] }()"),
+        sf!("fn() { return if (true) { return 7; } else { 1 } }() + 1"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_for_loops() {
+    let input: Vec<String> = vec![
+        sf!("let f = fn(xs) { for x in xs { if (x > 2) { return x; } } 0 }; f([1, 2, 3, 4])"),
+        sf!("let f = fn(n) { for i in 0..n { if (i * i > 50) { return i; } } -1 }; f(100)"),
+        sf!(
+            "let f = fn(h) { for k in h { if (h[k] == 2) { return k; } } 0 };
+        f({3: 2, 1: 5, 2: 2})"
+        ),
+        sf!("let f = fn() { for i in 0..10 { if (i < 5) { continue; } return i; } }; f()"),
+        sf!("let f = fn() { for i in 0..10 { if (i == 3) { break; } return i; } }; f()"),
+        sf!("let f = fn() { for i in 5..0 { return 1; } 2 }; f()"),
+        sf!("let f = fn() { for i in 0..3 { if (i == 1) { return fn() { i }; } } }; f()()"),
+        sf!("let x = 10; for x in [1, 2] { let y = x; } x"),
+        sf!("for x in [1, 2] { x }"),
+        sf!("for i in 0..4 { if (i == 2) { continue; } for j in 0..2 { break; } } 4"),
+        sf!("for x in [1] { let y = 2; } y"),
+        sf!("for x in 5 { }"),
+        sf!("1..true"),
+        sf!("0..3"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_assignment() {
+    let input: Vec<String> = vec![
+        sf!("let x = 1; x = 5; x"),
+        sf!("let x = 10; x += 5; x -= 3; x *= 2; x /= 4"),
+        sf!("let a = 1; let b = 2; a = b = 3; a + b"),
+        sf!("let s = 0; for i in 0..10 { s += i } s"),
+        sf!("let n = 0; while (n < 5) { n = n + 1 } n"),
+        sf!("let x = 1; let f = fn() { let x = 2; x = 7 }; f(); x"),
+        sf!("let c = fn() { let n = 0; fn() { n += 1 } }(); c(); c()"),
+        sf!("let s = \"a\"; s += \"b\""),
+        sf!("x = 1"),
+        sf!("y += 1"),
+        sf!("let x = 1; x /= 0"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_floats() {
+    let input: Vec<String> = vec![
+        sf!("1.5 + 2.25"),
+        sf!("-2.5 * 2"),
+        sf!("7 / 2.0"),
+        sf!("[1 == 1.0, 2 < 2.5, 1e-9]"),
+        sf!("let x = 1; x += 0.25; x"),
+        sf!("1.5 / 0"),
+        sf!("0.5..2"),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_comparison_and_modulo() {
+    let input: Vec<String> = vec![
+        sf!("[1 <= 1, 2 <= 1, 1 >= 1, 1 >= 2, 1.5 <= 2, 3 >= 2.5]"),
+        sf!("[17 % 5, -17 % 5, 7.5 % 2, 2 + 10 % 4 * 3]"),
+        sf!("let n = 0; for i in 0..20 { if (i % 3 == 0) { n += 1 } } n"),
+        sf!("10 % 0"),
+        sf!("10 % 0.0"),
+        sf!("\"a\" <= \"b\""),
+    ];
+
+    input.iter().for_each(|inp| assert_same(inp));
+}
+
+#[test]
+fn test_globals_persist_between_runs() {
+    let env = Rc::new(RefCell::new(Environment::new()));
+
+    run_vm("let counter = fn(x) { fn() { x } };", &env).unwrap();
+    run_vm("let c = counter(7);", &env).unwrap();
+    let evaluated = run_vm("c()", &env).unwrap();
+
+    match evaluated {
+        Object::Integer { value: 7 } => {}
+        _ => panic!("object is not 7, got={:#?}", evaluated),
+    }
+}
+
+// Runs `input` on both backends and checks they agree, errors and spans
+// included.
+fn assert_same(input: &str) {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+    let env = Rc::new(RefCell::new(Environment::new()));
+    let expected = eval_statements(program.statements, &env);
+
+    let env = Rc::new(RefCell::new(Environment::new()));
+    let got = run_vm(input, &env);
+
+    match (&expected, &got) {
+        (Ok(expected), Ok(got)) => {
+            if expected.get_type() != got.get_type() || expected.to_string() != got.to_string() {
+                panic!(
+                    "backends disagree on {:?}. evaluator={}, vm={}",
+                    input, expected, got
+                );
+            }
+        }
+        (Err(expected), Err(got)) => {
+            if expected != got {
+                panic!(
+                    "backends disagree on {:?}. evaluator={:#?}, vm={:#?}",
+                    input, expected, got
+                );
+            }
+        }
+        _ => panic!(
+            "backends disagree on {:?}. evaluator={:#?}, vm={:#?}",
+            input, expected, got
+        ),
+    }
+}
+
+fn run_vm(input: &str, env: &Rc<RefCell<Environment>>) -> Result<Object, RuntimeError> {
+    let l = Lexer::new(input.chars().collect());
+    let mut p = Parser::new(l);
+    let program = p.parse_program().unwrap();
+
+    vm::run(Rc::new(compile(&program.statements).unwrap()), env)
+}